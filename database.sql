@@ -17,10 +17,16 @@ CREATE EXTENSION pgroonga;
     Table Creation
 */
 
+DROP TABLE IF EXISTS user_word_status_change;
+DROP TABLE IF EXISTS user_word_context;
 DROP TABLE IF EXISTS read_article_data;
 DROP TABLE IF EXISTS saved_article;
+DROP TABLE IF EXISTS article_word;
+DROP TABLE IF EXISTS article_page_layout;
 DROP TABLE IF EXISTS article;
-DROP TABLE IF EXISTS user_word_data;
+DROP TABLE IF EXISTS user_word;
+DROP TABLE IF EXISTS user_dictionary_word;
+DROP TABLE IF EXISTS user_phrase;
 DROP TABLE IF EXISTS fruser;
 
 SET timezone = 'PRC';
@@ -33,20 +39,54 @@ CREATE TABLE fruser (
     created_on TIMESTAMP NOT NULL,
     study_lang VARCHAR(6),
     display_lang VARCHAR(6),
-    refresh_token VARCHAR
+    refresh_token VARCHAR,
+    merge_chinese_scripts BOOLEAN NOT NULL DEFAULT TRUE,
+    page_size INTEGER NOT NULL DEFAULT 150,
+    page_unit VARCHAR(5) NOT NULL DEFAULT 'words'
 );
 
 CREATE INDEX fruser_id_index ON fruser(id);
 CREATE INDEX fruser_index ON fruser(username);
 
-CREATE TABLE user_word_data (
-    fruser_id INTEGER UNIQUE NOT NULL,
+CREATE TABLE user_word (
+    fruser_id INTEGER NOT NULL,
     FOREIGN KEY (fruser_id) REFERENCES fruser(id),
-    word_status_data JSONB NOT NULL,
-    word_definition_data JSONB NOT NULL
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    status VARCHAR(10) CHECK (status IN ('learning', 'known', 'ignored')),
+    level INTEGER CHECK (level BETWEEN 1 AND 4),
+    definition VARCHAR,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    status_changed_on TIMESTAMP,
+    ease REAL NOT NULL DEFAULT 2.5,
+    review_interval INTEGER NOT NULL DEFAULT 0,
+    repetitions INTEGER NOT NULL DEFAULT 0,
+    due_on TIMESTAMP,
+    reviewed_on TIMESTAMP,
+    PRIMARY KEY (fruser_id, lang, word),
+    CHECK ((level IS NOT NULL) = (status IS NOT DISTINCT FROM 'learning'))
 );
 
-CREATE INDEX word_data_user_index ON user_word_data(fruser_id);
+CREATE INDEX user_word_status_index ON user_word(fruser_id, lang, status);
+CREATE INDEX user_word_due_index ON user_word(fruser_id, lang, due_on);
+
+CREATE TABLE user_dictionary_word (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    word VARCHAR(100) NOT NULL,
+    frequency INTEGER,
+    tag VARCHAR(20),
+    UNIQUE (fruser_id, word)
+);
+
+CREATE TABLE user_phrase (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    phrase VARCHAR(100) NOT NULL,
+    UNIQUE (fruser_id, lang, phrase)
+);
 
 CREATE TABLE article (
     id SERIAL PRIMARY KEY,
@@ -65,10 +105,26 @@ CREATE TABLE article (
     lang VARCHAR(6) NOT NULL,
     tags VARCHAR(50)[] NOT NULL,
 
+    detected_lang VARCHAR(6),
+    lang_confidence REAL,
+
     content VARCHAR NOT NULL,
+    content_format VARCHAR(10) NOT NULL DEFAULT 'plain',
 
     words VARCHAR[] NOT NULL,
     word_count INTEGER NOT NULL,
+
+    base_forms VARCHAR[] NOT NULL,
+    pinyin VARCHAR[],
+
+    char_offsets INTEGER[] NOT NULL,
+    byte_offsets INTEGER[] NOT NULL,
+    token_types VARCHAR[] NOT NULL,
+    paragraph_indices INTEGER[] NOT NULL,
+    block_types VARCHAR[] NOT NULL,
+
+    difficulty REAL,
+    vocabulary_size INTEGER NOT NULL DEFAULT 0,
     
     unique_words JSONB NOT NULL,
     unique_word_count INTEGER NOT NULL,
@@ -79,7 +135,11 @@ CREATE TABLE article (
     sentences JSONB,
     sentence_stops INTEGER[],
 
-    page_data JSONB NOT NULL
+    marks JSONB NOT NULL,
+    phrases JSONB NOT NULL,
+
+    needs_segmentation BOOLEAN NOT NULL DEFAULT FALSE,
+    segmentation_failed BOOLEAN NOT NULL DEFAULT FALSE
 );
 
 CREATE INDEX article_id_index ON article(id);
@@ -88,6 +148,29 @@ CREATE INDEX article_tag_index ON article USING pgroonga (tags);
 CREATE INDEX article_author_index ON article USING pgroonga (author);
 CREATE INDEX article_uploader_index ON article(uploader_id);
 CREATE INDEX article_lang_index ON article USING HASH (lang);
+CREATE INDEX article_difficulty_index ON article(difficulty);
+CREATE INDEX article_needs_segmentation_index ON article(id) WHERE needs_segmentation;
+
+CREATE TABLE article_word (
+    article_id INTEGER NOT NULL,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE CASCADE,
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    simplified VARCHAR,
+    PRIMARY KEY(article_id, word)
+);
+
+CREATE INDEX article_word_word_index ON article_word(lang, word);
+CREATE INDEX article_word_simplified_index ON article_word(simplified);
+
+CREATE TABLE article_page_layout (
+    article_id INTEGER NOT NULL,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE CASCADE,
+    page_size INTEGER NOT NULL,
+    page_unit VARCHAR(5) NOT NULL,
+    page_starts INTEGER[] NOT NULL,
+    PRIMARY KEY(article_id, page_size, page_unit)
+);
 
 CREATE TABLE saved_article (
     fruser_id INTEGER NOT NULL,
@@ -113,3 +196,35 @@ CREATE TABLE read_article_data (
 
 CREATE INDEX read_article_data_user_index ON read_article_data(fruser_id);
 CREATE INDEX read_article_data_article_index ON read_article_data(article_id);
+
+CREATE TABLE user_word_status_change (
+    id SERIAL PRIMARY KEY,
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    old_status VARCHAR(10),
+    new_status VARCHAR(10),
+    old_level INTEGER,
+    new_level INTEGER,
+    article_id INTEGER,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE SET NULL,
+    changed_on TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX user_word_status_change_index 
+    ON user_word_status_change(fruser_id, lang, changed_on);
+
+CREATE TABLE user_word_context (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    article_id INTEGER NOT NULL,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE CASCADE,
+    sentence VARCHAR NOT NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (fruser_id, lang, word, article_id, sentence)
+);
+
+CREATE INDEX user_word_context_index ON user_word_context(fruser_id, lang, word);
@@ -0,0 +1,43 @@
+/*
+    Moves each user's vocabulary out of the user_word_data JSONB documents and
+    into one user_word row per word. Run once against an existing database.
+*/
+
+BEGIN;
+
+CREATE TABLE user_word (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    status VARCHAR(10),
+    definition VARCHAR,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    status_changed_on TIMESTAMP,
+    PRIMARY KEY (fruser_id, lang, word)
+);
+
+CREATE INDEX user_word_status_index ON user_word(fruser_id, lang, status);
+
+/* a word listed under both statuses is kept as known */
+INSERT INTO user_word (fruser_id, lang, word, status, status_changed_on)
+SELECT DISTINCT ON (data.fruser_id, langs.key, words.word)
+    data.fruser_id, langs.key, words.word, statuses.key, NOW()
+FROM user_word_data data,
+    jsonb_each(data.word_status_data) langs,
+    jsonb_each(langs.value) statuses,
+    jsonb_object_keys(statuses.value) words(word)
+WHERE statuses.key IN ('learning', 'known')
+ORDER BY data.fruser_id, langs.key, words.word, statuses.key = 'known' DESC;
+
+INSERT INTO user_word (fruser_id, lang, word, definition)
+SELECT data.fruser_id, langs.key, definitions.key, definitions.value
+FROM user_word_data data,
+    jsonb_each(data.word_definition_data) langs,
+    jsonb_each_text(langs.value) definitions
+ON CONFLICT (fruser_id, lang, word) DO UPDATE SET definition = EXCLUDED.definition;
+
+DROP TABLE user_word_data;
+
+COMMIT;
@@ -0,0 +1,19 @@
+/*
+    Adds a review schedule to each word. The words already being learned are
+    due for review straight away.
+*/
+
+BEGIN;
+
+ALTER TABLE user_word
+    ADD COLUMN ease REAL NOT NULL DEFAULT 2.5,
+    ADD COLUMN review_interval INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN repetitions INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN due_on TIMESTAMP,
+    ADD COLUMN reviewed_on TIMESTAMP;
+
+UPDATE user_word SET due_on = NOW() WHERE status = 'learning';
+
+CREATE INDEX user_word_due_index ON user_word(fruser_id, lang, due_on);
+
+COMMIT;
@@ -0,0 +1,18 @@
+/*
+    Adds a level of familiarity to the words being learned, which start at
+    level 1, and the ignored status.
+*/
+
+BEGIN;
+
+ALTER TABLE user_word
+    ADD COLUMN level INTEGER;
+
+UPDATE user_word SET level = 1 WHERE status = 'learning';
+
+ALTER TABLE user_word
+    ADD CHECK (status IN ('learning', 'known', 'ignored')),
+    ADD CHECK (level BETWEEN 1 AND 4),
+    ADD CHECK ((level IS NOT NULL) = (status IS NOT DISTINCT FROM 'learning'));
+
+COMMIT;
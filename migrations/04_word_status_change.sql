@@ -0,0 +1,26 @@
+/*
+    Adds the history of word status changes. Changes made before it existed
+    aren't known, so the history starts empty.
+*/
+
+BEGIN;
+
+CREATE TABLE user_word_status_change (
+    id SERIAL PRIMARY KEY,
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    old_status VARCHAR(10),
+    new_status VARCHAR(10),
+    old_level INTEGER,
+    new_level INTEGER,
+    article_id INTEGER,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE SET NULL,
+    changed_on TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX user_word_status_change_index 
+    ON user_word_status_change(fruser_id, lang, changed_on);
+
+COMMIT;
@@ -0,0 +1,21 @@
+/*
+    Adds the sentences words were marked as learning in.
+*/
+
+BEGIN;
+
+CREATE TABLE user_word_context (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    article_id INTEGER NOT NULL,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE CASCADE,
+    sentence VARCHAR NOT NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (fruser_id, lang, word, article_id, sentence)
+);
+
+CREATE INDEX user_word_context_index ON user_word_context(fruser_id, lang, word);
+
+COMMIT;
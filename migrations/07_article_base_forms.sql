@@ -0,0 +1,11 @@
+/*
+    Adds the base forms of article words. Existing articles are left without
+    them until they're segmented again, which can't be done here.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN base_forms VARCHAR[];
+
+COMMIT;
@@ -0,0 +1,11 @@
+/*
+    Adds the setting to share word status between simplified and traditional
+    Chinese, which is on by default.
+*/
+
+BEGIN;
+
+ALTER TABLE fruser
+    ADD COLUMN merge_chinese_scripts BOOLEAN NOT NULL DEFAULT TRUE;
+
+COMMIT;
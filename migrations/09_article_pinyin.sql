@@ -0,0 +1,11 @@
+/*
+    Adds the pinyin of Chinese article words. Existing articles are left
+    without it until they're segmented again.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN pinyin VARCHAR[];
+
+COMMIT;
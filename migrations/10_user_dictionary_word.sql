@@ -0,0 +1,16 @@
+/*
+    Adds the words users add to their own segmentation dictionaries.
+*/
+
+BEGIN;
+
+CREATE TABLE user_dictionary_word (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    word VARCHAR(100) NOT NULL,
+    frequency INTEGER,
+    tag VARCHAR(20),
+    UNIQUE (fruser_id, word)
+);
+
+COMMIT;
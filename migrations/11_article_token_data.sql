@@ -0,0 +1,14 @@
+/*
+    Adds the offsets, types and paragraphs of article tokens. Existing
+    articles are left without them until they're segmented again.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN char_offsets INTEGER[],
+    ADD COLUMN byte_offsets INTEGER[],
+    ADD COLUMN token_types VARCHAR[],
+    ADD COLUMN paragraph_indices INTEGER[];
+
+COMMIT;
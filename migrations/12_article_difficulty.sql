@@ -0,0 +1,13 @@
+/*
+    Adds the difficulty of articles. Existing articles are left without one
+    until they're segmented again.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN difficulty REAL;
+
+CREATE INDEX article_difficulty_index ON article(difficulty);
+
+COMMIT;
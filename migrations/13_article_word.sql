@@ -0,0 +1,28 @@
+/*
+    Adds the index of the words in each article, which recommendations are
+    matched against, and the size of the vocabulary articles need. The words
+    of Chinese articles are also indexed in simplified characters. Existing
+    articles are indexed once they're segmented again.
+
+    This takes over from 06_article_word_simplified.sql, which couldn't run
+    before the table it alters was created.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN vocabulary_size INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE article_word (
+    article_id INTEGER NOT NULL,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE CASCADE,
+    lang VARCHAR(6) NOT NULL,
+    word VARCHAR NOT NULL,
+    simplified VARCHAR,
+    PRIMARY KEY(article_id, word)
+);
+
+CREATE INDEX article_word_word_index ON article_word(lang, word);
+CREATE INDEX article_word_simplified_index ON article_word(simplified);
+
+COMMIT;
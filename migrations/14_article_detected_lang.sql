@@ -0,0 +1,11 @@
+/*
+    Adds the language detected in article content and the confidence in it.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN detected_lang VARCHAR(6),
+    ADD COLUMN lang_confidence REAL;
+
+COMMIT;
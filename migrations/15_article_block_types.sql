@@ -0,0 +1,11 @@
+/*
+    Adds the block types of article paragraphs. Existing articles are left
+    without them until they're segmented again.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN block_types VARCHAR[];
+
+COMMIT;
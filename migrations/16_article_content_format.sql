@@ -0,0 +1,10 @@
+/*
+    Adds the format of article content. Existing articles are plain text.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN content_format VARCHAR(10) NOT NULL DEFAULT 'plain';
+
+COMMIT;
@@ -0,0 +1,27 @@
+/*
+    Adds the page size users read at and the page starts cached for each
+    article. Pages are computed from the token data now, so new articles
+    don't store page data, but it's kept for existing articles until they're
+    segmented again.
+*/
+
+BEGIN;
+
+ALTER TABLE fruser
+    ADD COLUMN page_size INTEGER NOT NULL DEFAULT 150,
+    ADD COLUMN page_unit VARCHAR(5) NOT NULL DEFAULT 'words';
+
+ALTER TABLE article
+    ADD COLUMN marks JSONB,
+    ALTER COLUMN page_data DROP NOT NULL;
+
+CREATE TABLE article_page_layout (
+    article_id INTEGER NOT NULL,
+    FOREIGN KEY (article_id) REFERENCES article(id) ON DELETE CASCADE,
+    page_size INTEGER NOT NULL,
+    page_unit VARCHAR(5) NOT NULL,
+    page_starts INTEGER[] NOT NULL,
+    PRIMARY KEY(article_id, page_size, page_unit)
+);
+
+COMMIT;
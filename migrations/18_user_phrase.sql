@@ -0,0 +1,19 @@
+/*
+    Adds the phrases users track and the phrases found in each article.
+    Existing articles are left without them until they're segmented again.
+*/
+
+BEGIN;
+
+CREATE TABLE user_phrase (
+    fruser_id INTEGER NOT NULL,
+    FOREIGN KEY (fruser_id) REFERENCES fruser(id),
+    lang VARCHAR(6) NOT NULL,
+    phrase VARCHAR(100) NOT NULL,
+    UNIQUE (fruser_id, lang, phrase)
+);
+
+ALTER TABLE article
+    ADD COLUMN phrases JSONB;
+
+COMMIT;
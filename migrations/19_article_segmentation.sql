@@ -0,0 +1,20 @@
+/*
+    Marks the existing articles to be segmented again, since the data computed
+    from their content can't be made here. The server segments them in the
+    background once it's started, and they're left out of article lists and
+    can't be read until it has. Articles it fails to segment are marked as
+    failed instead of being tried again on every start, and can be segmented
+    again by their uploaders.
+*/
+
+BEGIN;
+
+ALTER TABLE article
+    ADD COLUMN needs_segmentation BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN segmentation_failed BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE article SET needs_segmentation = TRUE;
+
+CREATE INDEX article_needs_segmentation_index ON article(id) WHERE needs_segmentation;
+
+COMMIT;
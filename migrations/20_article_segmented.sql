@@ -0,0 +1,28 @@
+/*
+    Drops the old page data and requires the token data of every article once
+    they've all been segmented again. Run it after the server has segmented
+    the articles marked by 19_article_segmentation.sql; it fails without
+    changing anything while any are left.
+*/
+
+BEGIN;
+
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM article WHERE needs_segmentation) THEN
+        RAISE EXCEPTION 'Some articles still need to be segmented';
+    END IF;
+END $$;
+
+ALTER TABLE article
+    DROP COLUMN page_data,
+    ALTER COLUMN base_forms SET NOT NULL,
+    ALTER COLUMN char_offsets SET NOT NULL,
+    ALTER COLUMN byte_offsets SET NOT NULL,
+    ALTER COLUMN token_types SET NOT NULL,
+    ALTER COLUMN paragraph_indices SET NOT NULL,
+    ALTER COLUMN block_types SET NOT NULL,
+    ALTER COLUMN marks SET NOT NULL,
+    ALTER COLUMN phrases SET NOT NULL;
+
+COMMIT;
@@ -0,0 +1,10 @@
+#!/bin/sh
+# Replaces data/zh/cedict.txt with the latest CC-CEDICT release, keeping its
+# header and leaving the definitions out.
+set -e
+
+url="https://www.mdbg.net/chinese/export/cedict/cedict_1_0_ts_utf-8_mdbg.txt.gz"
+out="$(dirname "$0")/../data/zh/cedict.txt"
+
+curl -fsSL "$url" | gunzip | sed 's| /.*$||' > "$out.tmp"
+mv "$out.tmp" "$out"
@@ -0,0 +1,23 @@
+#!/usr/bin/env python3
+# Replaces data/en/frequency.txt with the most frequent English words of
+# wordfreq (pip install wordfreq), limited to the words of words.txt.
+import os
+
+from wordfreq import top_n_list
+
+MAX_WORDS = 20000
+
+data = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "data", "en")
+frequency_path = os.path.join(data, "frequency.txt")
+
+with open(os.path.join(data, "words.txt"), encoding="utf-8") as words_file:
+    words = {line.strip() for line in words_file if not line.startswith("#")}
+
+with open(frequency_path, encoding="utf-8") as frequency_file:
+    header = [line for line in frequency_file if line.startswith("#")]
+
+ranked = [word for word in top_n_list("en", 100000) if word in words][:MAX_WORDS]
+
+with open(frequency_path, "w", encoding="utf-8") as frequency_file:
+    frequency_file.writelines(header)
+    frequency_file.writelines(word + "\n" for word in ranked)
@@ -2,6 +2,7 @@ use crate::models;
 use deadpool_postgres::Client;
 use futures::future;
 use serde_json::json;
+use std::collections::HashMap;
 use std::io;
 use tokio_pg_mapper::FromTokioPostgresRow;
 use tokio_postgres::types;
@@ -19,14 +20,17 @@ fn get_article_query(from_clause: &str, where_clause: &str, order_by_clause: &st
                 
                 lang, tags,
 
-                unique_word_count
+                unique_word_count, difficulty
 
                 FROM {} 
             WHERE 
+                needs_segmentation = false AND
                 COALESCE(lang = $1, TRUE) AND
-                COALESCE(title &@~ $2, TRUE)
+                COALESCE(title &@~ $2, TRUE) AND
+                ($5::REAL IS NULL OR difficulty >= $5) AND
+                ($6::REAL IS NULL OR difficulty <= $6)
                 {}
-            ORDER BY {} DESC 
+            ORDER BY {}
             LIMIT $4 
             OFFSET $3
         "#,
@@ -34,6 +38,74 @@ fn get_article_query(from_clause: &str, where_clause: &str, order_by_clause: &st
     )
 }
 
+/// Counts the words of each article the user knows and ignores, which the
+/// article word condition picks. The user is `$1`, and words stored under the
+/// languages in `$2`, by users who share one vocabulary between Chinese
+/// scripts, are also matched by the simplified words of Chinese articles.
+fn get_known_word_count_query(article_word_condition: &str) -> String {
+    format!(
+        r#"
+            article_user_word AS (
+                -- a shared entry wins over script specific ones
+                SELECT DISTINCT ON (aw.article_id, aw.word)
+                    aw.article_id, uw.status = 'ignored' AS is_ignored
+                    FROM article_word AS aw
+                    INNER JOIN user_word AS uw
+                        ON uw.fruser_id = $1 AND uw.status IS NOT NULL AND (
+                            (uw.lang = aw.lang AND uw.word = aw.word) OR
+                            (
+                                uw.lang = ANY($2::TEXT[]) AND 
+                                uw.word IN (aw.word, aw.simplified)
+                            )
+                        )
+                WHERE {}
+                ORDER BY 
+                    aw.article_id, aw.word, 
+                    array_position($2::TEXT[], uw.lang::TEXT) NULLS LAST
+            ),
+            known_word_count AS (
+                SELECT 
+                    article_id, 
+                    COUNT(*) FILTER (WHERE NOT is_ignored) AS known_count,
+                    COUNT(*) FILTER (WHERE is_ignored) AS ignored_count
+                    FROM article_user_word
+                GROUP BY article_id
+            )
+        "#,
+        article_word_condition
+    )
+}
+
+// the percentage of the vocabulary of an article `a`, less the words the user
+// ignores, which they know or are learning, and the number of words left
+const ARTICLE_COMPREHENSION_COLUMNS: &str = r#"
+    CAST(
+        COALESCE(
+            ROUND(
+                100 * COALESCE(k.known_count, 0) / 
+                    NULLIF(a.vocabulary_size - COALESCE(k.ignored_count, 0), 0)::NUMERIC,
+                1
+            ),
+            100
+        )
+        AS REAL
+    ) AS comprehension,
+    CAST(
+        a.vocabulary_size - COALESCE(k.known_count, 0) - COALESCE(k.ignored_count, 0)
+        AS INTEGER
+    ) AS new_word_count
+"#;
+
+/// Orders an article list by difficulty when asked to, and otherwise by the
+/// given column, newest or best matching first.
+fn get_article_order_by(sort: &Option<String>, default_order_by: &str) -> String {
+    match sort.as_deref() {
+        Some("easiest") => "difficulty ASC NULLS LAST".to_string(),
+        Some("hardest") => "difficulty DESC NULLS LAST".to_string(),
+        _ => format!("{} DESC", default_order_by),
+    }
+}
+
 fn extract_opt_inc_param<'a, T, U>(
     params: &mut [&'a (dyn ToSql + Sync)],
     current_param: &mut usize,
@@ -117,7 +189,7 @@ pub mod user {
         user_id: &i32,
         update: &models::db::UpdateUserOpt,
     ) -> Result<(), &'static str> {
-        let mut params: [&'_ (dyn ToSql + Sync); 7] = [&0; 7];
+        let mut params: [&'_ (dyn ToSql + Sync); 10] = [&0; 10];
         let mut current_param: usize = 0;
 
         let mut update_statements: Vec<String> = vec![];
@@ -168,6 +240,27 @@ pub mod user {
             "refresh_token",
             &mut add_to_statement,
         );
+        extract_opt_inc_param(
+            &mut params,
+            &mut current_param,
+            &update.merge_chinese_scripts,
+            "merge_chinese_scripts",
+            &mut add_to_statement,
+        );
+        extract_opt_inc_param(
+            &mut params,
+            &mut current_param,
+            &update.page_size,
+            "page_size",
+            &mut add_to_statement,
+        );
+        extract_opt_inc_param(
+            &mut params,
+            &mut current_param,
+            &update.page_unit,
+            "page_unit",
+            &mut add_to_statement,
+        );
 
         let set_clause = update_statements.join(",");
 
@@ -218,28 +311,13 @@ pub mod user {
     }
 
     #[inline]
-    async fn prepare_user_creation_statements(
+    async fn prepare_user_creation_statement(
         trans: &deadpool_postgres::Transaction<'_>,
-    ) -> Result<(Statement, Statement), tokio_postgres::error::Error> {
-        let insert_user_ft = trans.prepare(
+    ) -> Result<Statement, tokio_postgres::error::Error> {
+        trans.prepare(
             "INSERT INTO fruser (username, display_name, pass, created_on, study_lang, display_lang, refresh_token)
-                VALUES ($1, $2, $3, NOW(), $4, $5, $6) RETURNING id, display_name, study_lang, display_lang",
-        );
-
-        let insert_word_data_ft = trans
-            .prepare(
-                r#"
-                INSERT INTO user_word_data (fruser_id, word_status_data, word_definition_data)
-                VALUES
-                    (
-                        $1,
-                        '{ "en": { "learning": {}, "known": {} }, "zh": { "learning": {}, "known": {} } }',
-                        '{ "en": {}, "zh": {} }'
-                    )
-            "#
-            );
-
-        future::try_join(insert_user_ft, insert_word_data_ft).await
+                VALUES ($1, $2, $3, NOW(), $4, $5, $6) RETURNING id, display_name, study_lang, display_lang, merge_chinese_scripts, page_size, page_unit",
+        ).await
     }
 
     pub async fn create_user(
@@ -260,8 +338,8 @@ pub mod user {
             }
         };
 
-        let (insert_user, insert_word_data) = match prepare_user_creation_statements(&trans).await {
-            Ok((insert_user, insert_word_data)) => (insert_user, insert_word_data),
+        let insert_user = match prepare_user_creation_statement(&trans).await {
+            Ok(insert_user) => insert_user,
             Err(err) => {
                 eprintln!("{}", err);
                 return user_err;
@@ -295,13 +373,6 @@ pub mod user {
             }
         };
 
-        let insert_word_data_result = trans.query_opt(&insert_word_data, &[&user.id]).await;
-
-        if let Err(err) = insert_word_data_result {
-            eprintln!("{}", err);
-            return user_err;
-        }
-
         if let Err(err) = trans.commit().await {
             eprintln!("{}", err);
             return user_err;
@@ -313,16 +384,48 @@ pub mod user {
     pub mod word_data {
         use super::*;
 
+        /// Builds one page of the user's word data from their vocabulary rows,
+        /// ordered by language and word, along with whether there are more.
+        /// Words stored under `shared_langs`, by users who share one vocabulary
+        /// between Chinese scripts, are also matched by the simplified words of
+        /// the article in the filter.
         pub async fn get_user_word_data(
             client: &Client,
             user_id: &i32,
-        ) -> Result<models::db::UserWordData, &'static str> {
+            shared_langs: &[String],
+            offset: &i64,
+            limit: &i64,
+            filter: &models::db::WordDataFilter,
+        ) -> Result<(models::db::UserWordData, bool), &'static str> {
             let statement = match client
                 .prepare(
                     r#"
-                    SELECT word_status_data, word_definition_data
-                        FROM user_word_data
-                    WHERE fruser_id = $1
+                    SELECT lang, word, status, level, definition
+                        FROM user_word AS uw
+                    WHERE 
+                        fruser_id = $1 AND 
+                        ($2::VARCHAR[] IS NULL OR lang = ANY($2)) AND
+                        (
+                            $4::INTEGER IS NULL OR EXISTS (
+                                SELECT 1 
+                                    FROM article_word AS aw
+                                    INNER JOIN article AS a
+                                        ON a.id = aw.article_id
+                                WHERE 
+                                    aw.article_id = $4 AND
+                                    (a.is_system OR NOT a.is_private OR a.uploader_id = $1) AND
+                                    (
+                                        (uw.lang = aw.lang AND uw.word = aw.word) OR
+                                        (
+                                            uw.lang = ANY($3::TEXT[]) AND 
+                                            uw.word IN (aw.word, aw.simplified)
+                                        )
+                                    )
+                            )
+                        )
+                    ORDER BY lang, word
+                    LIMIT $6
+                    OFFSET $5
                 "#,
                 )
                 .await
@@ -334,256 +437,378 @@ pub mod user {
                 }
             };
 
-            match client.query_one(&statement, &[user_id]).await {
-                Ok(result) => match models::db::UserWordData::from_row_ref(&result) {
-                    Ok(word_data) => Ok(word_data),
-                    Err(err) => {
-                        eprintln!("{}", err);
-                        Err("Error getting word data")
-                    }
-                },
+            // one more row than the page tells whether there are more
+            let mut rows = match client
+                .query(
+                    &statement,
+                    &[
+                        user_id,
+                        &filter.langs,
+                        &shared_langs,
+                        &filter.article_id,
+                        offset,
+                        &(limit + 1),
+                    ],
+                )
+                .await
+            {
+                Ok(rows) => rows,
                 Err(err) => {
                     eprintln!("{}", err);
-                    Err("Error getting word data")
+                    return Err("Error getting word data");
+                }
+            };
+            let has_more = rows.len() as i64 > *limit;
+            rows.truncate(*limit as usize);
+
+            let mut word_status_data = json!({});
+            let mut word_definition_data = json!({});
+            let mut word_langs: Vec<String> = vec![];
+            let mut words: Vec<String> = vec![];
+
+            for row in rows {
+                let lang: String = row.get(0);
+                let word: String = row.get(1);
+                word_langs.push(lang.clone());
+                words.push(word.clone());
+                let status: Option<String> = row.get(2);
+                let level: Option<i32> = row.get(3);
+                let definition: Option<String> = row.get(4);
+
+                if word_status_data.get(&lang).is_none() {
+                    word_status_data[&lang] = json!({ "learning": {}, "known": {}, "ignored": {} });
+                    word_definition_data[&lang] = json!({});
                 }
-            }
-        }
 
-        async fn get_word_status_statement(
-            client: &Client,
-            new_status: &str,
-        ) -> Result<Statement, Error> {
-            match new_status {
-                "known" | "learning" => {
-                    let old_status = if new_status == "known" {
-                        "learning"
-                    } else {
-                        "known"
-                    };
-
-                    client
-                        .prepare_typed(
-                            &format!(
-                                r#"
-                                UPDATE user_word_data
-                                SET word_status_data = 
-                                    jsonb_set(
-                                        (word_status_data #- 
-                                            CAST(FORMAT('{{%s, {1}, %s}}', $1, $2) AS TEXT[])
-                                        ), 
-                                        CAST(FORMAT('{{%s, {0}, %s}}', $1, $2) AS TEXT[]),
-                                        '1'
-                                    )
-                                WHERE fruser_id = $3;
-                            "#,
-                                new_status, old_status
-                            )[..],
-                            &[Type::TEXT, Type::TEXT],
-                        )
-                        .await
+                // learning words are listed with their level
+                if let Some(status) = status {
+                    word_status_data[&lang][status][&word] = json!(level.unwrap_or(1));
                 }
-                "new" => {
-                    client
-                        .prepare_typed(
-                            r#"
-                            UPDATE user_word_data
-                            SET word_status_data = word_status_data 
-                                #- CAST(FORMAT('{%s, known, %s}', $1, $2) AS TEXT[])
-                                #- CAST(FORMAT('{%s, learning, %s}', $1, $2) AS TEXT[])
-                            WHERE fruser_id = $3
-                        "#,
-                            &[Type::TEXT, Type::TEXT],
-                        )
-                        .await
+
+                if let Some(definition) = definition {
+                    word_definition_data[&lang][&word] = json!(definition);
                 }
-                _ => panic!("new_status is invalid: {}", new_status),
             }
+
+            let word_context_data =
+                get_word_context_data(client, user_id, &word_langs, &words).await?;
+
+            Ok((
+                models::db::UserWordData {
+                    word_status_data,
+                    word_definition_data,
+                    word_context_data,
+                },
+                has_more,
+            ))
         }
 
-        pub async fn update_word_status(
+        const MAX_WORD_CONTEXTS: i64 = 5;
+
+        /// Lists the latest sentences each of the words of the languages at the
+        /// same indices was marked in, as
+        /// `{ lang: { word: [{ sentence, article_id }] } }`.
+        async fn get_word_context_data(
             client: &Client,
             user_id: &i32,
-            lang: &String,
-            word: &String,
-            new_status: &String,
-        ) -> Result<(), &'static str> {
-            let statement_result = get_word_status_statement(client, &new_status[..]).await;
-
-            let statement = match statement_result {
+            langs: &[String],
+            words: &[String],
+        ) -> Result<serde_json::Value, &'static str> {
+            let statement = match client
+                .prepare(
+                    r#"
+                    SELECT lang, word, sentence, article_id
+                        FROM (
+                            SELECT 
+                                *, 
+                                ROW_NUMBER() OVER (
+                                    PARTITION BY lang, word ORDER BY created_on DESC
+                                ) AS context_number
+                                FROM user_word_context
+                            WHERE 
+                                fruser_id = $1 AND 
+                                (lang, word) IN (
+                                    SELECT * FROM unnest($2::VARCHAR[], $3::VARCHAR[])
+                                )
+                        ) AS c
+                    WHERE context_number <= $4
+                    ORDER BY lang, word, context_number
+                "#,
+                )
+                .await
+            {
                 Ok(statement) => statement,
                 Err(err) => {
                     eprintln!("{}", err);
-                    return Err("Error updating word status");
+                    return Err("Error getting word data");
                 }
             };
 
-            match client.execute(&statement, &[lang, word, user_id]).await {
-                Ok(_) => Ok(()),
+            let rows = match client
+                .query(&statement, &[user_id, &langs, &words, &MAX_WORD_CONTEXTS])
+                .await
+            {
+                Ok(rows) => rows,
                 Err(err) => {
                     eprintln!("{}", err);
-                    Err("Error updating word status")
+                    return Err("Error getting word data");
                 }
-            }
-        }
+            };
 
-        fn get_batch_update_json_strings(
-            words: &[String],
-            new_status: &str,
-        ) -> (serde_json::Value, String) {
-            let mut json_dict = json!({});
+            let mut word_context_data = json!({});
 
-            let map = match json_dict {
-                serde_json::Value::Object(ref mut map) => map,
-                _ => panic!("json_dict serde_json::Value isn't an Object!"),
-            };
+            for row in rows {
+                let lang: String = row.get(0);
+                let word: String = row.get(1);
+                let sentence: String = row.get(2);
+                let article_id: i32 = row.get(3);
 
-            let parameter_offset = if new_status == "new" { 3 } else { 4 };
-            let mut delete_str = String::from("");
+                if word_context_data.get(&lang).is_none() {
+                    word_context_data[&lang] = json!({});
+                }
 
-            for (i, word) in words.iter().enumerate() {
-                map.insert(word.to_lowercase(), json!(1));
-                delete_str += &format!("#- ${} ", i + parameter_offset)[..];
+                let context = json!({ "sentence": sentence, "article_id": article_id });
+                match word_context_data[&lang].get_mut(&word) {
+                    Some(serde_json::Value::Array(contexts)) => contexts.push(context),
+                    _ => word_context_data[&lang][&word] = json!([context]),
+                }
             }
 
-            (json_dict, delete_str)
+            Ok(word_context_data)
         }
 
-        async fn get_batch_update_statement(
-            client: &Client,
-            new_status: &str,
-            json_delete_str: String,
-            word_count: usize,
-        ) -> Result<Statement, Error> {
-            let mut types: Vec<Type> = vec![Type::TEXT, Type::INT4];
-
-            let formatted_string = match new_status {
-                "new" => format!(
+        /// Keeps the sentence of an article a word was marked in.
+        pub async fn add_word_context(
+            trans: &deadpool_postgres::Transaction<'_>,
+            user_id: &i32,
+            lang: &str,
+            word: &str,
+            article_id: &i32,
+            sentence: &str,
+        ) -> Result<(), &'static str> {
+            let statement = trans
+                .prepare(
                     r#"
-                    UPDATE user_word_data
-                    SET word_status_data = 
-                        jsonb_set(
-                            jsonb_set(
-                                word_status_data, 
-                                CAST(FORMAT('{{%s, known}}', $1) AS TEXT[]),
-                                (word_status_data)->$1->'known'
-                                    {0}
-                            ),
-                            CAST(FORMAT('{{%s, learning}}', $1) AS TEXT[]),
-                            (
-                                (word_status_data)->$1->'learning'
-                                    {0}
-                            )
-                        )
-                    WHERE fruser_id = $2;
+                    INSERT INTO user_word_context (fruser_id, lang, word, article_id, sentence)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (fruser_id, lang, word, article_id, sentence) 
+                        DO UPDATE SET created_on = NOW()
                 "#,
-                    json_delete_str
-                ),
-                "learning" | "known" => {
-                    types.push(Type::JSONB);
-
-                    let old_status = match new_status {
-                        "learning" => "known",
-                        "known" => "learning",
-                        _ => panic!(
-                            "Unsupported batch word status update new status: {}",
-                            new_status
-                        ),
-                    };
-
-                    // $1: lang
-                    // $2: user_id
-                    // $3: insert_json
-                    // $4..$n: (n - 4 + 1) words
-                    // {0}: new_status
-                    // {1}: old_status
-                    // {2}: json_delete_str
-                    let formatted_string = format!(
-                        r#"
-                        UPDATE user_word_data
-                        SET word_status_data = 
-                            jsonb_set(
-                                jsonb_set(
-                                    word_status_data, 
-                                    CAST(FORMAT('{{%s, {0}}}', $1) AS TEXT[]),
-                                    (word_status_data)->$1->'{0}' ||
-                                    $3
-                                ),
-                                CAST(FORMAT('{{%s, {1}}}', $1) AS TEXT[]),
-                                (
-                                    (word_status_data)->$1->'{1}'
-                                        {2}
-                                )
-                            )
-                        WHERE fruser_id = $2;
-                    "#,
-                        new_status, old_status, json_delete_str
-                    );
+                )
+                .await
+                .unwrap();
 
-                    formatted_string
+            match trans
+                .execute(&statement, &[user_id, &lang, &word, article_id, &sentence])
+                .await
+            {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error adding word context")
                 }
-
-                _ => panic!(
-                    "Unsupported batch word status update new status: {}",
-                    new_status
-                ),
-            };
-
-            types.reserve(word_count);
-            for _ in 0..word_count {
-                types.push(Type::TEXT_ARRAY);
             }
-
-            client.prepare_typed(&formatted_string, &types).await
         }
 
-        fn build_batch_update_params<'a>(
-            lang: &'a String,
-            user_id: &'a i32,
-            insert_json: &'a serde_json::Value,
-            words: &'a [Vec<&'a str>],
+        // $1: lang
+        // $2: word, or an array of words for a batch update
+        // $3: user_id
+        // $4: article_id the change was made from, if any
+        // $5: new_status, unless it's new
+        // $6: level, for a learning word
+        // A word set to new keeps its row only while it has a definition. A word
+        // starts its review schedule over whenever its status changes, and is
+        // only due for review while it's being learned. Every change is added
+        // to the word's history, along with the article while it exists.
+        async fn get_word_status_statement(
+            trans: &deadpool_postgres::Transaction<'_>,
             new_status: &str,
-        ) -> Vec<&'a (dyn ToSql + Sync)> {
-            let mut params: Vec<&(dyn ToSql + Sync)> = vec![lang, user_id];
-
-            if new_status != "new" {
-                params.push(insert_json);
-            }
+            is_batch: bool,
+        ) -> Result<Statement, Error> {
+            let (word_type, word_condition, word_values) = if is_batch {
+                (
+                    Type::TEXT_ARRAY,
+                    "word = ANY($2)",
+                    "SELECT $3, $1, word, $5, $6, NOW(), NOW(), {0} FROM unnest($2::VARCHAR[]) AS word",
+                )
+            } else {
+                (
+                    Type::TEXT,
+                    "word = $2",
+                    "VALUES ($3, $1, $2, $5, $6, NOW(), NOW(), {0})",
+                )
+            };
 
-            for word in words {
-                params.push(word);
+            if new_status == "new" {
+                return trans
+                    .prepare_typed(
+                        &format!(
+                            r#"
+                            WITH old AS (
+                                SELECT word, status, level
+                                    FROM user_word
+                                WHERE 
+                                    fruser_id = $3 AND lang = $1 AND {0} AND 
+                                    status IS NOT NULL
+                            ),
+                            cleared AS (
+                                UPDATE user_word
+                                SET 
+                                    status = NULL, 
+                                    level = NULL,
+                                    status_changed_on = NOW(), 
+                                    updated_on = NOW(),
+                                    due_on = NULL,
+                                    review_interval = 0,
+                                    repetitions = 0
+                                WHERE 
+                                    fruser_id = $3 AND lang = $1 AND {0} AND 
+                                    definition IS NOT NULL
+                            ),
+                            deleted AS (
+                                DELETE FROM user_word
+                                WHERE 
+                                    fruser_id = $3 AND lang = $1 AND {0} AND 
+                                    definition IS NULL
+                            )
+                            INSERT INTO user_word_status_change 
+                                (fruser_id, lang, word, old_status, old_level, article_id)
+                            SELECT $3, $1, word, status, level, (SELECT id FROM article WHERE id = $4)
+                                FROM old
+                        "#,
+                            word_condition
+                        )[..],
+                        &[Type::TEXT, word_type, Type::INT4, Type::INT4],
+                    )
+                    .await;
             }
 
-            params
+            // a word moved between learning levels keeps its review schedule
+            trans
+                .prepare_typed(
+                    &format!(
+                        r#"
+                        WITH old AS (
+                            SELECT word, status, level
+                                FROM user_word
+                            WHERE fruser_id = $3 AND lang = $1 AND {0}
+                        ),
+                        changed AS (
+                            INSERT INTO user_word 
+                                (fruser_id, lang, word, status, level, status_changed_on, updated_on, due_on)
+                            {1}
+                            ON CONFLICT (fruser_id, lang, word) DO UPDATE
+                            SET 
+                                status = EXCLUDED.status, 
+                                level = EXCLUDED.level,
+                                status_changed_on = NOW(), 
+                                updated_on = NOW(),
+                                due_on = CASE 
+                                    WHEN user_word.status IS DISTINCT FROM EXCLUDED.status 
+                                    THEN EXCLUDED.due_on 
+                                    ELSE user_word.due_on 
+                                END,
+                                review_interval = CASE 
+                                    WHEN user_word.status IS DISTINCT FROM EXCLUDED.status 
+                                    THEN 0 
+                                    ELSE user_word.review_interval 
+                                END,
+                                repetitions = CASE 
+                                    WHEN user_word.status IS DISTINCT FROM EXCLUDED.status 
+                                    THEN 0 
+                                    ELSE user_word.repetitions 
+                                END
+                            WHERE 
+                                user_word.status IS DISTINCT FROM EXCLUDED.status OR 
+                                user_word.level IS DISTINCT FROM EXCLUDED.level
+                            RETURNING word, status, level
+                        )
+                        INSERT INTO user_word_status_change 
+                            (fruser_id, lang, word, old_status, new_status, old_level, new_level, article_id)
+                        SELECT 
+                            $3, $1, changed.word, old.status, changed.status, old.level, changed.level,
+                            (SELECT id FROM article WHERE id = $4)
+                            FROM changed
+                            LEFT JOIN old ON old.word = changed.word
+                    "#,
+                        word_condition,
+                        word_values.replace(
+                            "{0}",
+                            "CASE WHEN $5 = 'learning' THEN NOW() END"
+                        )
+                    )[..],
+                    &[
+                        Type::TEXT,
+                        word_type,
+                        Type::INT4,
+                        Type::INT4,
+                        Type::TEXT,
+                        Type::INT4,
+                    ],
+                )
+                .await
         }
 
-        fn get_vectored_words(words: &[String]) -> Vec<Vec<&str>> {
-            let mut vectored_words: Vec<Vec<&str>> = vec![];
+        /// Sets the status of a word, which the caller has checked exists. Only
+        /// learning words have a level.
+        pub async fn update_word_status(
+            trans: &deadpool_postgres::Transaction<'_>,
+            user_id: &i32,
+            lang: &String,
+            word: &String,
+            new_status: &String,
+            level: &Option<i32>,
+            article_id: &Option<i32>,
+        ) -> Result<(), &'static str> {
+            let statement_result = get_word_status_statement(trans, &new_status[..], false).await;
 
-            for word in words {
-                vectored_words.push(vec![&word[..]]);
-            }
+            let statement = match statement_result {
+                Ok(statement) => statement,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return Err("Error updating word status");
+                }
+            };
 
-            vectored_words
+            let result = if new_status == "new" {
+                trans
+                    .execute(&statement, &[lang, word, user_id, article_id])
+                    .await
+            } else {
+                trans
+                    .execute(
+                        &statement,
+                        &[lang, word, user_id, article_id, new_status, level],
+                    )
+                    .await
+            };
+
+            match result {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error updating word status")
+                }
+            }
         }
 
         pub async fn batch_update_word_status(
-            client: &Client,
+            trans: &deadpool_postgres::Transaction<'_>,
             user_id: &i32,
             lang: &String,
             words: &[String],
             new_status: &String,
+            level: &Option<i32>,
+            article_id: &Option<i32>,
         ) -> Result<(), &'static str> {
-            let (insert_json, json_delete_str) =
-                get_batch_update_json_strings(words, &new_status[..]);
-
-            let statement = match get_batch_update_statement(
-                client,
-                &new_status[..],
-                json_delete_str,
-                words.len(),
-            )
-            .await
-            {
+            // a word can only be written once by a single insert
+            let mut words = words.to_vec();
+            words.sort();
+            words.dedup();
+
+            let statement_result = get_word_status_statement(trans, &new_status[..], true).await;
+
+            let statement = match statement_result {
                 Ok(statement) => statement,
                 Err(err) => {
                     eprintln!("{}", err);
@@ -591,17 +816,20 @@ pub mod user {
                 }
             };
 
-            let vectored_words = get_vectored_words(words);
-
-            let params = build_batch_update_params(
-                lang,
-                user_id,
-                &insert_json,
-                &vectored_words[..],
-                &new_status[..],
-            );
+            let result = if new_status == "new" {
+                trans
+                    .execute(&statement, &[lang, &words, user_id, article_id])
+                    .await
+            } else {
+                trans
+                    .execute(
+                        &statement,
+                        &[lang, &words, user_id, article_id, new_status, level],
+                    )
+                    .await
+            };
 
-            match client.execute(&statement, &params[..]).await {
+            match result {
                 Ok(_) => Ok(()),
                 Err(err) => {
                     eprintln!("{}", err);
@@ -610,6 +838,68 @@ pub mod user {
             }
         }
 
+        /// Counts the words that moved into each status in every period of the
+        /// timeline, by language. The periods are days, weeks or months, and
+        /// the timeline ends with the current one.
+        pub async fn get_timeline(
+            client: &Client,
+            user_id: &i32,
+            langs: Option<&[String]>,
+            period: &str,
+            period_count: &i64,
+        ) -> Result<Vec<models::db::TimelinePeriod>, &'static str> {
+            let statement = match client
+                .prepare(
+                    r#"
+                    SELECT 
+                        lang,
+                        date_trunc($3, changed_on) AS period_start,
+                        COUNT(*) FILTER (
+                            WHERE new_status = 'learning' AND 
+                            old_status IS DISTINCT FROM 'learning'
+                        ) AS learning,
+                        COUNT(*) FILTER (WHERE new_status = 'known') AS known,
+                        COUNT(*) FILTER (WHERE new_status = 'ignored') AS ignored,
+                        COUNT(*) FILTER (WHERE new_status IS NULL) AS reset
+                        FROM user_word_status_change
+                    WHERE 
+                        fruser_id = $1 AND 
+                        ($2::VARCHAR[] IS NULL OR lang = ANY($2)) AND
+                        changed_on >= 
+                            date_trunc($3, NOW()) - ($4::BIGINT - 1) * CAST('1 ' || $3 AS INTERVAL)
+                    GROUP BY lang, period_start
+                    ORDER BY period_start, lang
+                "#,
+                )
+                .await
+            {
+                Ok(statement) => statement,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return Err("Error getting timeline");
+                }
+            };
+
+            match client
+                .query(&statement, &[user_id, &langs, &period, period_count])
+                .await
+            {
+                Ok(rows) => rows
+                    .iter()
+                    .map(|row| {
+                        models::db::TimelinePeriod::from_row_ref(row).map_err(|err| {
+                            eprintln!("{}", err);
+                            "Error getting timeline"
+                        })
+                    })
+                    .collect::<Result<Vec<models::db::TimelinePeriod>, &'static str>>(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting timeline")
+                }
+            }
+        }
+
         pub async fn update_word_definition(
             client: &Client,
             user_id: &i32,
@@ -620,14 +910,10 @@ pub mod user {
             let statement = match client
                 .prepare_typed(
                     r#"
-                        UPDATE user_word_data
-                        SET word_definition_data = 
-                            jsonb_set(
-                                word_definition_data, 
-                                CAST(FORMAT('{ %s, %s }', $1, $2) AS TEXT[]), 
-                                FORMAT('"%s"', $3)::jsonb
-                            )
-                        WHERE fruser_id = $4
+                        INSERT INTO user_word (fruser_id, lang, word, definition, updated_on)
+                        VALUES ($4, $1, $2, $3, NOW())
+                        ON CONFLICT (fruser_id, lang, word) DO UPDATE
+                        SET definition = EXCLUDED.definition, updated_on = NOW()
                 "#,
                     &[Type::TEXT, Type::TEXT, Type::TEXT],
                 )
@@ -817,20 +1103,368 @@ pub mod user {
             }
         }
     }
-}
 
-pub mod article {
-    use super::*;
+    pub mod dictionary {
+        use super::*;
 
-    pub async fn create_article(
-        trans: &deadpool_postgres::Transaction<'_>,
-        article_meta_data: models::db::ArticleMetadata,
-        article_main_data: models::db::ArticleMainData,
-        words: Vec<String>,
-    ) -> Result<models::db::NewArticle, &'static str> {
-        let statement = match trans
-            .prepare(
-                r#"
+        pub async fn get_dictionary_words(
+            client: &Client,
+            user_id: &i32,
+        ) -> Result<Vec<models::db::DictionaryWord>, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    SELECT word, frequency, tag
+                        FROM user_dictionary_word
+                    WHERE fruser_id = $1
+                    ORDER BY word
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client.query(&statement, &[user_id]).await {
+                Ok(rows) => rows
+                    .iter()
+                    .map(|row| {
+                        models::db::DictionaryWord::from_row_ref(row).map_err(|err| {
+                            eprintln!("{}", err);
+                            "Error getting dictionary words"
+                        })
+                    })
+                    .collect::<Result<Vec<models::db::DictionaryWord>, &'static str>>(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting dictionary words")
+                }
+            }
+        }
+
+        pub async fn add_dictionary_word(
+            client: &Client,
+            user_id: &i32,
+            word: &models::db::DictionaryWord,
+        ) -> Result<(), &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    INSERT INTO user_dictionary_word (fruser_id, word, frequency, tag)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (fruser_id, word) DO UPDATE 
+                    SET frequency = EXCLUDED.frequency, tag = EXCLUDED.tag
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client
+                .execute(
+                    &statement,
+                    &[user_id, &word.word, &word.frequency, &word.tag],
+                )
+                .await
+            {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error adding dictionary word")
+                }
+            }
+        }
+
+        pub async fn remove_dictionary_word(
+            client: &Client,
+            user_id: &i32,
+            word: &String,
+        ) -> Result<(), &'static str> {
+            let statement = client
+                .prepare("DELETE FROM user_dictionary_word WHERE fruser_id = $1 AND word = $2")
+                .await
+                .unwrap();
+
+            match client.execute(&statement, &[user_id, word]).await {
+                Ok(0) => Err("missing"),
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error removing dictionary word")
+                }
+            }
+        }
+    }
+
+    pub mod phrase {
+        use super::*;
+
+        pub async fn get_phrases(
+            client: &Client,
+            user_id: &i32,
+        ) -> Result<Vec<models::db::UserPhrase>, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    SELECT lang, phrase
+                        FROM user_phrase
+                    WHERE fruser_id = $1
+                    ORDER BY lang, phrase
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client.query(&statement, &[user_id]).await {
+                Ok(rows) => rows
+                    .iter()
+                    .map(|row| {
+                        models::db::UserPhrase::from_row_ref(row).map_err(|err| {
+                            eprintln!("{}", err);
+                            "Error getting phrases"
+                        })
+                    })
+                    .collect::<Result<Vec<models::db::UserPhrase>, &'static str>>(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting phrases")
+                }
+            }
+        }
+
+        pub async fn get_lang_phrases(
+            client: &Client,
+            user_id: &i32,
+            lang: &str,
+        ) -> Result<Vec<String>, &'static str> {
+            let statement = client
+                .prepare("SELECT phrase FROM user_phrase WHERE fruser_id = $1 AND lang = $2")
+                .await
+                .unwrap();
+
+            match client.query(&statement, &[user_id, &lang]).await {
+                Ok(rows) => Ok(rows.iter().map(|row| row.get(0)).collect()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting phrases")
+                }
+            }
+        }
+
+        pub async fn add_phrase(
+            client: &Client,
+            user_id: &i32,
+            phrase: &models::db::UserPhrase,
+        ) -> Result<(), &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    INSERT INTO user_phrase (fruser_id, lang, phrase)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT DO NOTHING
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client
+                .execute(&statement, &[user_id, &phrase.lang, &phrase.phrase])
+                .await
+            {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error adding phrase")
+                }
+            }
+        }
+
+        pub async fn remove_phrase(
+            client: &Client,
+            user_id: &i32,
+            phrase: &models::db::UserPhrase,
+        ) -> Result<(), &'static str> {
+            let statement = client
+                .prepare(
+                    "DELETE FROM user_phrase WHERE fruser_id = $1 AND lang = $2 AND phrase = $3",
+                )
+                .await
+                .unwrap();
+
+            match client
+                .execute(&statement, &[user_id, &phrase.lang, &phrase.phrase])
+                .await
+            {
+                Ok(0) => Err("missing"),
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error removing phrase")
+                }
+            }
+        }
+    }
+
+    pub mod review {
+        use super::*;
+        use crate::review::Schedule;
+
+        pub async fn get_due_words(
+            client: &Client,
+            user_id: &i32,
+            lang: &str,
+            limit: &i64,
+        ) -> Result<Vec<models::db::ReviewWord>, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    SELECT lang, word, status, level, definition, ease, review_interval, repetitions, due_on
+                        FROM user_word
+                    WHERE 
+                        fruser_id = $1 AND lang = $2 AND 
+                        status = 'learning' AND due_on <= NOW()
+                    ORDER BY due_on
+                    LIMIT $3
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client.query(&statement, &[user_id, &lang, limit]).await {
+                Ok(rows) => rows
+                    .iter()
+                    .map(|row| {
+                        models::db::ReviewWord::from_row_ref(row).map_err(|err| {
+                            eprintln!("{}", err);
+                            "Error getting due words"
+                        })
+                    })
+                    .collect::<Result<Vec<models::db::ReviewWord>, &'static str>>(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting due words")
+                }
+            }
+        }
+
+        /// Returns the review schedule of a word the user is learning.
+        pub async fn get_schedule(
+            client: &Client,
+            user_id: &i32,
+            lang: &str,
+            word: &str,
+        ) -> Result<Schedule, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    SELECT ease, review_interval, repetitions
+                        FROM user_word
+                    WHERE fruser_id = $1 AND lang = $2 AND word = $3 AND status = 'learning'
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client.query_opt(&statement, &[user_id, &lang, &word]).await {
+                Ok(Some(row)) => Ok(Schedule {
+                    ease: row.get(0),
+                    interval: row.get(1),
+                    repetitions: row.get(2),
+                }),
+                Ok(None) => Err("missing"),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting review schedule")
+                }
+            }
+        }
+
+        /// Saves a word's schedule after a review. A word whose schedule is mature
+        /// becomes known and leaves the review queue, which is kept in its history.
+        pub async fn update_schedule(
+            client: &Client,
+            user_id: &i32,
+            lang: &str,
+            word: &str,
+            schedule: &Schedule,
+        ) -> Result<models::db::ReviewWord, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    WITH reviewed AS (
+                        UPDATE user_word
+                        SET 
+                            ease = $4,
+                            review_interval = $5,
+                            repetitions = $6,
+                            reviewed_on = NOW(),
+                            updated_on = NOW(),
+                            status = CASE WHEN $7 THEN 'known' ELSE status END,
+                            level = CASE WHEN $7 THEN NULL ELSE level END,
+                            status_changed_on = CASE WHEN $7 THEN NOW() ELSE status_changed_on END,
+                            due_on = CASE WHEN $7 THEN NULL ELSE NOW() + $5 * INTERVAL '1 day' END
+                        WHERE fruser_id = $1 AND lang = $2 AND word = $3 AND status = 'learning'
+                        RETURNING 
+                            lang, word, status, level, definition, ease, review_interval, repetitions, 
+                            due_on
+                    ),
+                    promoted AS (
+                        INSERT INTO user_word_status_change 
+                            (fruser_id, lang, word, old_status, new_status, old_level)
+                        SELECT 
+                            $1, $2, $3, 'learning', 'known',
+                            (
+                                SELECT level FROM user_word 
+                                WHERE fruser_id = $1 AND lang = $2 AND word = $3
+                            )
+                            FROM reviewed
+                        WHERE $7
+                    )
+                    SELECT * FROM reviewed
+                "#,
+                )
+                .await
+                .unwrap();
+
+            let result = client
+                .query_opt(
+                    &statement,
+                    &[
+                        user_id,
+                        &lang,
+                        &word,
+                        &schedule.ease,
+                        &schedule.interval,
+                        &schedule.repetitions,
+                        &schedule.is_mature(),
+                    ],
+                )
+                .await;
+
+            match result {
+                Ok(Some(row)) => models::db::ReviewWord::from_row_ref(&row).map_err(|err| {
+                    eprintln!("{}", err);
+                    "Error updating review schedule"
+                }),
+                Ok(None) => Err("missing"),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error updating review schedule")
+                }
+            }
+        }
+    }
+}
+
+pub mod article {
+    use super::*;
+
+    pub async fn create_article(
+        trans: &deadpool_postgres::Transaction<'_>,
+        article_meta_data: models::db::ArticleMetadata,
+        article_main_data: models::db::ArticleMainData,
+        words: Vec<String>,
+    ) -> Result<models::db::NewArticle, &'static str> {
+        let statement = match trans
+            .prepare(
+                r#"
                 INSERT INTO article 
                         (
                            title, author, created_on, uploader_id, content_description,
@@ -849,7 +1483,17 @@ pub mod article {
 
                            sentences, sentence_stops,
 
-                           page_data
+                           marks, phrases,
+
+                           base_forms, pinyin,
+
+                           char_offsets, byte_offsets, token_types, paragraph_indices, block_types,
+
+                           difficulty,
+
+                           detected_lang, lang_confidence,
+
+                           content_format
                         ) 
                 VALUES (
                     $1, $2, NOW(), $3, $4,
@@ -868,10 +1512,20 @@ pub mod article {
                     
                     $16, $17,
 
-                    $18
+                    $18, $19,
+
+                    $20, $21,
+
+                    $22, $23, $24, $25, $26,
+
+                    $27,
+
+                    $28, $29,
+
+                    $30
                 ) 
                 RETURNING 
-                    id, title, created_on
+                    id, title, created_on, lang, detected_lang, lang_confidence
             "#,
             )
             .await
@@ -911,7 +1565,19 @@ pub mod article {
                     &article_main_data.stop_word_map,
                     &article_main_data.sentences,
                     &article_main_data.sentence_stops,
-                    &article_main_data.page_data,
+                    &article_main_data.marks,
+                    &article_main_data.phrases,
+                    &article_main_data.base_forms,
+                    &article_main_data.pinyin,
+                    &article_main_data.char_offsets,
+                    &article_main_data.byte_offsets,
+                    &article_main_data.token_types,
+                    &article_main_data.paragraph_indices,
+                    &article_main_data.block_types,
+                    &article_main_data.difficulty,
+                    &article_main_data.detected_lang,
+                    &article_main_data.lang_confidence,
+                    &article_main_data.content_format,
                 ],
             )
             .await
@@ -938,7 +1604,7 @@ pub mod article {
         main_data_opt: Option<models::db::ArticleMainData>,
         words_opt: Option<Vec<String>>,
     ) -> Result<(), &'static str> {
-        let mut params: [&'_ (dyn ToSql + Sync); 18] = [&0; 18];
+        let mut params: [&'_ (dyn ToSql + Sync); 30] = [&0; 30];
         let mut current_param: usize = 0;
 
         let mut update_statements: Vec<String> = vec![];
@@ -1023,9 +1689,24 @@ pub mod article {
 
                 let models::db::ArticleMainData {
                     content,
+                    content_format,
 
                     word_count,
 
+                    base_forms,
+                    pinyin,
+
+                    char_offsets,
+                    byte_offsets,
+                    token_types,
+                    paragraph_indices,
+                    block_types,
+
+                    difficulty,
+
+                    detected_lang,
+                    lang_confidence,
+
                     unique_words,
                     unique_word_count,
 
@@ -1035,7 +1716,8 @@ pub mod article {
                     sentences,
                     sentence_stops,
 
-                    page_data,
+                    marks,
+                    phrases,
                 } = main_data;
 
                 params[current_param] = content;
@@ -1043,119 +1725,633 @@ pub mod article {
                 current_param += 1;
                 types.push(Type::TEXT);
 
+                params[current_param] = content_format;
+                add_to_statement("content_format", &current_param);
+                current_param += 1;
+                types.push(Type::TEXT);
+
                 params[current_param] = word_count;
                 add_to_statement("word_count", &current_param);
                 current_param += 1;
                 types.push(Type::INT4);
 
-                params[current_param] = unique_words;
-                add_to_statement("unique_words", &current_param);
-                current_param += 1;
-                types.push(Type::JSONB);
+                params[current_param] = base_forms;
+                add_to_statement("base_forms", &current_param);
+                current_param += 1;
+                types.push(Type::TEXT_ARRAY);
+
+                params[current_param] = pinyin;
+                add_to_statement("pinyin", &current_param);
+                current_param += 1;
+                types.push(Type::TEXT_ARRAY);
+
+                params[current_param] = char_offsets;
+                add_to_statement("char_offsets", &current_param);
+                current_param += 1;
+                types.push(Type::INT4_ARRAY);
+
+                params[current_param] = byte_offsets;
+                add_to_statement("byte_offsets", &current_param);
+                current_param += 1;
+                types.push(Type::INT4_ARRAY);
+
+                params[current_param] = token_types;
+                add_to_statement("token_types", &current_param);
+                current_param += 1;
+                types.push(Type::TEXT_ARRAY);
+
+                params[current_param] = paragraph_indices;
+                add_to_statement("paragraph_indices", &current_param);
+                current_param += 1;
+                types.push(Type::INT4_ARRAY);
+
+                params[current_param] = block_types;
+                add_to_statement("block_types", &current_param);
+                current_param += 1;
+                types.push(Type::TEXT_ARRAY);
+
+                params[current_param] = difficulty;
+                add_to_statement("difficulty", &current_param);
+                current_param += 1;
+                types.push(Type::FLOAT4);
+
+                params[current_param] = detected_lang;
+                add_to_statement("detected_lang", &current_param);
+                current_param += 1;
+                types.push(Type::TEXT);
+
+                params[current_param] = lang_confidence;
+                add_to_statement("lang_confidence", &current_param);
+                current_param += 1;
+                types.push(Type::FLOAT4);
+
+                params[current_param] = unique_words;
+                add_to_statement("unique_words", &current_param);
+                current_param += 1;
+                types.push(Type::JSONB);
+
+                params[current_param] = unique_word_count;
+                add_to_statement("unique_word_count", &current_param);
+                current_param += 1;
+                types.push(Type::INT4);
+
+                params[current_param] = word_index_map;
+                add_to_statement("word_index_map", &current_param);
+                current_param += 1;
+                types.push(Type::JSONB);
+
+                params[current_param] = stop_word_map;
+                add_to_statement("stop_word_map", &current_param);
+                current_param += 1;
+                types.push(Type::JSONB);
+
+                params[current_param] = sentences;
+                add_to_statement("sentences", &current_param);
+                current_param += 1;
+                types.push(Type::JSONB);
+
+                params[current_param] = sentence_stops;
+                add_to_statement("sentence_stops", &current_param);
+                current_param += 1;
+                types.push(Type::INT4_ARRAY);
+
+                params[current_param] = marks;
+                add_to_statement("marks", &current_param);
+                current_param += 1;
+                types.push(Type::JSONB);
+
+                params[current_param] = phrases;
+                add_to_statement("phrases", &current_param);
+                current_param += 1;
+                types.push(Type::JSONB);
+            } else {
+                panic!("main_data_opt was Some but words was None");
+            }
+        }
+
+        params[current_param] = &article_id;
+        current_param += 1;
+        types.push(Type::INT4);
+
+        params[current_param] = &user_id;
+        current_param += 1;
+        types.push(Type::INT4);
+
+        // the content was segmented again, replacing any migrated data
+        if main_data_opt_ref.is_some() {
+            update_statements.push(" needs_segmentation = FALSE".to_string());
+            update_statements.push(" segmentation_failed = FALSE".to_string());
+        }
+
+        let set_clause = update_statements.join(",");
+
+        let statement = match trans
+            .prepare_typed(
+                &format!(
+                    r#"
+                        UPDATE article
+                        SET {}
+                        WHERE id = ${} AND uploader_id = ${}
+                    "#,
+                    set_clause,
+                    current_param - 1,
+                    current_param
+                )[..],
+                &types[..]
+            )
+            .await
+        {
+            Ok(statement) => statement,
+            Err(err) => {
+                eprintln!("{}", err);
+                return Err("Error editing article");
+            }
+        };
+
+        if let Err(err) = trans.execute(&statement, &params[..current_param]).await {
+            eprintln!("{}", err);
+            return Err("Error editing article");
+        }
+
+        // the cached pages were made from the old content
+        if main_data_opt_ref.is_some() {
+            let clear_result = trans
+                .execute(
+                    "DELETE FROM article_page_layout WHERE article_id = $1",
+                    &[&article_id],
+                )
+                .await;
+
+            if let Err(err) = clear_result {
+                eprintln!("{}", err);
+                return Err("Error editing article");
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn does_own_article(
+        client: &Client,
+        article_id: i32,
+        user_id: i32,
+    ) -> Result<(String, String, String), &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    SELECT content, lang, content_format FROM article 
+                    WHERE 
+                        id = $1 AND 
+                        uploader_id = $2
+                "#,
+            )
+            .await
+            .unwrap();
+
+        match client.query_opt(&statement, &[&article_id, &user_id]).await {
+            Ok(ref row_opt) => match row_opt {
+                Some(ref row) => Ok((row.get(0), row.get(1), row.get(2))),
+                None => Err("missing"),
+            },
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error getting article")
+            }
+        }
+    }
+
+    /// Returns the id and uploader of the articles migrated without the data
+    /// computed from their content, leaving out those that failed to be
+    /// segmented before.
+    pub async fn get_unsegmented_articles(
+        client: &Client,
+    ) -> Result<Vec<(i32, i32)>, &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    SELECT id, uploader_id FROM article 
+                    WHERE needs_segmentation AND NOT segmentation_failed
+                "#,
+            )
+            .await
+            .unwrap();
+
+        match client.query(&statement, &[]).await {
+            Ok(rows) => Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect()),
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error getting articles")
+            }
+        }
+    }
+
+    pub async fn set_segmentation_failed(
+        client: &Client,
+        article_id: &i32,
+    ) -> Result<(), &'static str> {
+        let statement = client
+            .prepare("UPDATE article SET segmentation_failed = TRUE WHERE id = $1")
+            .await
+            .unwrap();
+
+        match client.execute(&statement, &[article_id]).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error editing article")
+            }
+        }
+    }
+
+    /// Replaces the words an article is indexed under for recommendations.
+    /// The language is taken from the article, so it has to be called after the
+    /// article is created or edited.
+    pub async fn index_article_words(
+        trans: &deadpool_postgres::Transaction<'_>,
+        article_id: &i32,
+        vocabulary: &[String],
+        simplified_vocabulary: &Option<Vec<String>>,
+    ) -> Result<(), &'static str> {
+        let statements = future::try_join3(
+            trans.prepare("DELETE FROM article_word WHERE article_id = $1"),
+            trans.prepare(
+                r#"
+                    INSERT INTO article_word (article_id, lang, word, simplified)
+                        SELECT a.id, a.lang, w.word, w.simplified 
+                            FROM article AS a, unnest($2::TEXT[], $3::TEXT[]) AS w(word, simplified)
+                    WHERE a.id = $1
+                "#,
+            ),
+            trans.prepare("UPDATE article SET vocabulary_size = $2 WHERE id = $1"),
+        )
+        .await;
+
+        let (delete_statement, insert_statement, size_statement) = match statements {
+            Ok(statements) => statements,
+            Err(err) => {
+                eprintln!("{}", err);
+                return Err("Error indexing article");
+            }
+        };
+
+        let vocabulary_size = vocabulary.len() as i32;
+
+        if let Err(err) = trans.execute(&delete_statement, &[article_id]).await {
+            eprintln!("{}", err);
+            return Err("Error indexing article");
+        }
+
+        if let Err(err) = trans
+            .execute(
+                &insert_statement,
+                &[article_id, &vocabulary, simplified_vocabulary],
+            )
+            .await
+        {
+            eprintln!("{}", err);
+            return Err("Error indexing article");
+        }
+
+        match trans
+            .execute(&size_statement, &[article_id, &vocabulary_size])
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error indexing article")
+            }
+        }
+    }
+
+    pub async fn get_article_page_source(
+        client: &Client,
+        article_id: &i32,
+    ) -> Result<Option<models::db::ArticlePageSource>, &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    SELECT 
+                        words, token_types, sentence_stops, paragraph_indices, block_types, marks
+                        FROM article 
+                    WHERE id = $1 AND needs_segmentation = false
+                "#,
+            )
+            .await
+            .unwrap();
+
+        match client.query_opt(&statement, &[article_id]).await {
+            Ok(ref row_opt) => match row_opt {
+                Some(ref row) => match models::db::ArticlePageSource::from_row_ref(row) {
+                    Ok(page_source) => Ok(Some(page_source)),
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        Err("Error getting article")
+                    }
+                },
+                None => Ok(None),
+            },
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error getting article")
+            }
+        }
+    }
 
-                params[current_param] = unique_word_count;
-                add_to_statement("unique_word_count", &current_param);
-                current_param += 1;
-                types.push(Type::INT4);
+    /// Returns the sentences of an article the user can read.
+    pub async fn get_article_sentence_data(
+        client: &Client,
+        article_id: &i32,
+        user_id: &i32,
+    ) -> Result<Option<models::db::ArticleSentenceData>, &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    SELECT sentences, sentence_stops
+                        FROM article 
+                    WHERE 
+                        id = $1 AND 
+                        (is_system OR NOT is_private OR uploader_id = $2) AND
+                        is_deleted = false
+                "#,
+            )
+            .await
+            .unwrap();
 
-                params[current_param] = word_index_map;
-                add_to_statement("word_index_map", &current_param);
-                current_param += 1;
-                types.push(Type::JSONB);
+        match client.query_opt(&statement, &[article_id, user_id]).await {
+            Ok(ref row_opt) => match row_opt {
+                Some(ref row) => match models::db::ArticleSentenceData::from_row_ref(row) {
+                    Ok(sentence_data) => Ok(Some(sentence_data)),
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        Err("Error getting article")
+                    }
+                },
+                None => Ok(None),
+            },
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error getting article")
+            }
+        }
+    }
 
-                params[current_param] = stop_word_map;
-                add_to_statement("stop_word_map", &current_param);
-                current_param += 1;
-                types.push(Type::JSONB);
+    pub async fn get_article_index_data(
+        client: &Client,
+        article_id: &i32,
+    ) -> Result<Option<models::db::ArticleIndexData>, &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    SELECT 
+                        word_index_map, stop_word_map, base_forms, pinyin, phrases
+                        FROM article 
+                    WHERE id = $1 AND needs_segmentation = false
+                "#,
+            )
+            .await
+            .unwrap();
 
-                params[current_param] = sentences;
-                add_to_statement("sentences", &current_param);
-                current_param += 1;
-                types.push(Type::JSONB);
+        match client.query_opt(&statement, &[article_id]).await {
+            Ok(ref row_opt) => match row_opt {
+                Some(ref row) => match models::db::ArticleIndexData::from_row_ref(row) {
+                    Ok(index_data) => Ok(Some(index_data)),
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        Err("Error getting article")
+                    }
+                },
+                None => Ok(None),
+            },
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error getting article")
+            }
+        }
+    }
 
-                params[current_param] = sentence_stops;
-                add_to_statement("sentence_stops", &current_param);
-                current_param += 1;
-                types.push(Type::INT4_ARRAY);
+    /// Returns the page starts cached for the article at the page size, if
+    /// it's been paginated at that size since its content last changed.
+    pub async fn get_page_starts(
+        client: &Client,
+        article_id: &i32,
+        page_size: &i32,
+        page_unit: &str,
+    ) -> Result<Option<Vec<i32>>, &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    SELECT page_starts FROM article_page_layout 
+                    WHERE article_id = $1 AND page_size = $2 AND page_unit = $3
+                "#,
+            )
+            .await
+            .unwrap();
 
-                params[current_param] = page_data;
-                add_to_statement("page_data", &current_param);
-                current_param += 1;
-                types.push(Type::JSONB);
-            } else {
-                panic!("main_data_opt was Some but words was None");
+        match client
+            .query_opt(&statement, &[article_id, page_size, &page_unit])
+            .await
+        {
+            Ok(row_opt) => Ok(row_opt.map(|row| row.get(0))),
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error getting article pages")
             }
         }
+    }
 
-        params[current_param] = &article_id;
-        current_param += 1;
-        types.push(Type::INT4);
-
-        params[current_param] = &user_id;
-        current_param += 1;
-        types.push(Type::INT4);
+    pub async fn save_page_starts(
+        client: &Client,
+        article_id: &i32,
+        page_size: &i32,
+        page_unit: &str,
+        page_starts: &[i32],
+    ) -> Result<(), &'static str> {
+        let statement = client
+            .prepare(
+                r#"
+                    INSERT INTO article_page_layout (article_id, page_size, page_unit, page_starts)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .await
+            .unwrap();
 
-        let set_clause = update_statements.join(",");
+        match client
+            .execute(
+                &statement,
+                &[article_id, page_size, &page_unit, &page_starts],
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                eprintln!("{}", err);
+                Err("Error saving article pages")
+            }
+        }
+    }
 
-        let statement = match trans
-            .prepare_typed(
+    /// Returns the comprehension and new word count of each of the articles,
+    /// matching the user's words against the article word index like the
+    /// recommendations do.
+    pub async fn get_article_comprehension(
+        client: &Client,
+        user_id: &i32,
+        shared_langs: &[String],
+        article_ids: &[i32],
+    ) -> Result<HashMap<i32, (f32, i32)>, &'static str> {
+        let statement = match client
+            .prepare(
                 &format!(
                     r#"
-                        UPDATE article
-                        SET {}
-                        WHERE id = ${} AND uploader_id = ${}
+                        WITH {}
+                        SELECT a.id, {}
+                            FROM article AS a
+                            LEFT JOIN known_word_count AS k
+                                ON k.article_id = a.id
+                        WHERE a.id = ANY($3)
                     "#,
-                    set_clause,
-                    current_param - 1,
-                    current_param
+                    get_known_word_count_query("aw.article_id = ANY($3)"),
+                    ARTICLE_COMPREHENSION_COLUMNS
                 )[..],
-                &types[..]
             )
             .await
         {
             Ok(statement) => statement,
             Err(err) => {
                 eprintln!("{}", err);
-                return Err("Error editing article");
+                return Err("Error getting comprehension");
             }
         };
 
-        match trans.execute(&statement, &params[..current_param]).await {
-            Ok(_) => Ok(()),
+        match client
+            .query(&statement, &[user_id, &shared_langs, &article_ids])
+            .await
+        {
+            Ok(rows) => Ok(rows
+                .iter()
+                .map(|row| {
+                    (
+                        row.get("id"),
+                        (row.get("comprehension"), row.get("new_word_count")),
+                    )
+                })
+                .collect()),
             Err(err) => {
                 eprintln!("{}", err);
-                Err("Error editing article")
+                Err("Error getting comprehension")
             }
         }
     }
 
-    pub async fn does_own_article(
+    /// Lists the public articles the user hasn't read, closest to the given
+    /// comprehension band first. The user's words are matched against the
+    /// article word index rather than each article's word data. Words stored
+    /// under `shared_langs`, by users who share one vocabulary between Chinese
+    /// scripts, are also matched by the simplified words of Chinese articles.
+    pub async fn get_recommended_article_list(
         client: &Client,
-        article_id: i32,
-        user_id: i32,
-    ) -> Result<(String, String), &'static str> {
-        let statement = client
+        user_id: &i32,
+        shared_langs: &[String],
+        offset: &i64,
+        limit: &Option<i64>,
+        filter: &models::db::RecommendationFilter,
+    ) -> Result<Vec<models::db::ArticleListItem>, &'static str> {
+        let statement = match client
             .prepare(
-                r#"
-                    SELECT content, lang FROM article 
-                    WHERE 
-                        id = $1 AND 
-                        uploader_id = $2
-                "#,
+                &format!(
+                    r#"
+                        WITH {},
+                        article_comprehension AS (
+                            SELECT 
+                                a.id, a.title, a.author, a.created_on, a.uploader_id, 
+                                a.content_description,
+    
+                                a.is_system, a.is_private,
+    
+                                a.lang, a.tags,
+    
+                                a.unique_word_count, a.difficulty,
+    
+                                {}
+                                
+                                FROM article AS a
+                                LEFT JOIN known_word_count AS k
+                                    ON k.article_id = a.id
+                            WHERE
+                                a.is_deleted = false AND
+                                a.is_private = false AND
+                                a.needs_segmentation = false AND
+                                a.vocabulary_size > 0 AND
+                                COALESCE(a.lang = $3, TRUE) AND
+                                ($4::TEXT IS NULL OR $4 = ANY(a.tags)) AND
+                                NOT EXISTS (
+                                    SELECT 1 FROM read_article_data AS r
+                                    WHERE r.fruser_id = $1 AND r.article_id = a.id
+                                )
+                        )
+                        SELECT * FROM article_comprehension
+                        ORDER BY 
+                            GREATEST($5::REAL - comprehension, comprehension - $6::REAL, 0) ASC,
+                            ABS(comprehension - ($5::REAL + $6::REAL) / 2) ASC,
+                            created_on DESC
+                        LIMIT $8
+                        OFFSET $7
+                    "#,
+                    get_known_word_count_query("COALESCE(aw.lang = $3, TRUE)"),
+                    ARTICLE_COMPREHENSION_COLUMNS
+                )[..],
             )
             .await
-            .unwrap();
+        {
+            Ok(statement) => statement,
+            Err(err) => {
+                eprintln!("{}", err);
+                return Err("Error getting articles");
+            }
+        };
 
-        match client.query_opt(&statement, &[&article_id, &user_id]).await {
-            Ok(ref row_opt) => match row_opt {
-                Some(ref row) => Ok((row.get(0), row.get(1))),
-                None => Err("missing"),
-            },
+        let rows = match client
+            .query(
+                &statement,
+                &[
+                    user_id,
+                    &shared_langs,
+                    &filter.lang,
+                    &filter.tag,
+                    &filter.min_comprehension,
+                    &filter.max_comprehension,
+                    offset,
+                    match limit {
+                        Some(limit) => limit,
+                        None => &(10i64),
+                    },
+                ],
+            )
+            .await
+        {
+            Ok(rows) => rows,
             Err(err) => {
                 eprintln!("{}", err);
-                Err("Error getting article")
+                return Err("Error getting articles");
             }
-        }
+        };
+
+        rows.iter()
+            .map(|row| match models::db::SimpleArticle::from_row_ref(row) {
+                Ok(article) => Ok(models::db::ArticleListItem {
+                    article,
+                    comprehension: row.get("comprehension"),
+                    new_word_count: row.get("new_word_count"),
+                }),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting articles")
+                }
+            })
+            .collect()
     }
 
     pub mod system {
@@ -1173,17 +2369,24 @@ pub mod article {
                         
                         is_system, is_private,
 
-                        lang, tags, 
+                        lang, tags, content_format,
 
                         word_count, unique_word_count,
                         
                         word_index_map, stop_word_map,
 
-                        page_data
+                        base_forms, pinyin,
+
+                        char_offsets, byte_offsets, token_types, paragraph_indices, block_types,
+
+                        phrases
                         
                         FROM article 
                     WHERE 
-                        id = $1 AND is_system = true AND is_deleted = false
+                        id = $1 AND 
+                        is_system = true AND 
+                        is_deleted = false AND 
+                        needs_segmentation = false
                 "#,
                 )
                 .await
@@ -1207,14 +2410,37 @@ pub mod article {
             }
         }
 
+        pub async fn is_system_article(
+            client: &Client,
+            article_id: &i32,
+        ) -> Result<bool, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    SELECT id FROM article 
+                    WHERE 
+                        id = $1 AND is_system = true AND is_deleted = false
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client.query_opt(&statement, &[article_id]).await {
+                Ok(row_opt) => Ok(row_opt.is_some()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting article")
+                }
+            }
+        }
+
         pub async fn get_system_article_list(
             client: &Client,
             offset: &i64,
-            lang: &Option<String>,
-            search: &Option<String>,
             limit: &Option<i64>,
+            filter: &models::db::ArticleListFilter,
         ) -> Result<Vec<models::db::SimpleArticle>, io::Error> {
-            let order_by_str = if search.is_some() {
+            let order_by_str = if filter.search.is_some() {
                 "pgroonga_score(tableoid, ctid)"
             } else {
                 "created_on"
@@ -1230,7 +2456,7 @@ pub mod article {
                             is_private = false AND 
                             is_deleted = false
                         "#,
-                        order_by_str,
+                        &get_article_order_by(&filter.sort, order_by_str),
                     )[..],
                     &[Type::TEXT, Type::TEXT],
                 )
@@ -1241,13 +2467,15 @@ pub mod article {
                 .query(
                     &statement,
                     &[
-                        lang,
-                        search,
+                        &filter.lang,
+                        &filter.search,
                         offset,
                         match limit {
                             Some(limit) => limit,
                             None => &(10i64),
                         },
+                        &filter.min_difficulty,
+                        &filter.max_difficulty,
                     ],
                 )
                 .await
@@ -1276,20 +2504,25 @@ pub mod article {
                             
                         is_system, is_private,
 
-                        lang, tags, 
+                        lang, tags, content_format,
 
                         word_count, unique_word_count,
                         
                         word_index_map, stop_word_map,
 
-                        page_data
+                        base_forms, pinyin,
+
+                        char_offsets, byte_offsets, token_types, paragraph_indices, block_types,
+
+                        phrases
 
                         FROM article 
                     WHERE 
                         id = $1 AND 
                         (NOT is_private OR uploader_id = $2) AND
                         is_system = false AND
-                        is_deleted = false
+                        is_deleted = false AND
+                        needs_segmentation = false
                 "#,
                 )
                 .await
@@ -1313,6 +2546,34 @@ pub mod article {
             }
         }
 
+        pub async fn can_read_article(
+            client: &Client,
+            article_id: &i32,
+            user_id: &i32,
+        ) -> Result<bool, &'static str> {
+            let statement = client
+                .prepare(
+                    r#"
+                    SELECT id FROM article 
+                    WHERE 
+                        id = $1 AND 
+                        (NOT is_private OR uploader_id = $2) AND
+                        is_system = false AND
+                        is_deleted = false
+                "#,
+                )
+                .await
+                .unwrap();
+
+            match client.query_opt(&statement, &[article_id, user_id]).await {
+                Ok(row_opt) => Ok(row_opt.is_some()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Err("Error getting article")
+                }
+            }
+        }
+
         pub async fn user_delete_article(
             client: &Client,
             user_id: &i32,
@@ -1414,11 +2675,10 @@ pub mod article {
             client: &Client,
             user_id: &i32,
             offset: &i64,
-            lang: &Option<String>,
-            search: &Option<String>,
             limit: &Option<i64>,
+            filter: &models::db::ArticleListFilter,
         ) -> Result<Vec<models::db::SimpleArticle>, io::Error> {
-            let order_by_str = if search.is_some() {
+            let order_by_str = if filter.search.is_some() {
                 "pgroonga_score(a.tableoid, a.ctid)"
             } else {
                 "s.saved_on"
@@ -1434,11 +2694,11 @@ pub mod article {
                         "#,
                         r#"
                             AND
-                            s.fruser_id = $5 AND 
-                            (NOT a.is_private OR a.uploader_id = $5) AND
+                            s.fruser_id = $7 AND 
+                            (NOT a.is_private OR a.uploader_id = $7) AND
                             is_deleted = false
                         "#,
-                        order_by_str,
+                        &get_article_order_by(&filter.sort, order_by_str),
                     )[..],
                     &[Type::TEXT, Type::TEXT],
                 )
@@ -1449,13 +2709,15 @@ pub mod article {
                 .query(
                     &statement,
                     &[
-                        lang,
-                        search,
+                        &filter.lang,
+                        &filter.search,
                         offset,
                         match limit {
                             Some(limit) => limit,
                             None => &(10i64),
                         },
+                        &filter.min_difficulty,
+                        &filter.max_difficulty,
                         user_id,
                     ],
                 )
@@ -1473,11 +2735,10 @@ pub mod article {
             req_user_id: &i32,
             want_user_id: &i32,
             offset: &i64,
-            lang: &Option<String>,
-            search: &Option<String>,
             limit: &Option<i64>,
+            filter: &models::db::ArticleListFilter,
         ) -> Result<Vec<models::db::SimpleArticle>, io::Error> {
-            let order_by_str = if search.is_some() {
+            let order_by_str = if filter.search.is_some() {
                 "pgroonga_score(tableoid, ctid)"
             } else {
                 "created_on"
@@ -1489,11 +2750,11 @@ pub mod article {
                         "article",
                         r#"
                             AND
-                            uploader_id = $5 AND 
-                            ($6 OR NOT is_private) AND
+                            uploader_id = $7 AND 
+                            ($8 OR NOT is_private) AND
                             is_deleted = false
                         "#,
-                        order_by_str,
+                        &get_article_order_by(&filter.sort, order_by_str),
                     )[..],
                     &[Type::TEXT, Type::TEXT],
                 )
@@ -1504,13 +2765,15 @@ pub mod article {
                 .query(
                     &statement,
                     &[
-                        lang,
-                        search,
+                        &filter.lang,
+                        &filter.search,
                         offset,
                         match limit {
                             Some(limit) => limit,
                             None => &(10i64),
                         },
+                        &filter.min_difficulty,
+                        &filter.max_difficulty,
                         want_user_id,
                         &(want_user_id == req_user_id),
                     ],
@@ -1528,11 +2791,10 @@ pub mod article {
             client: &Client,
             req_user_id: &i32,
             offset: &i64,
-            lang: &Option<String>,
-            search: &Option<String>,
             limit: &Option<i64>,
+            filter: &models::db::ArticleListFilter,
         ) -> Result<Vec<models::db::SimpleArticle>, io::Error> {
-            let order_by_str = if search.is_some() {
+            let order_by_str = if filter.search.is_some() {
                 "pgroonga_score(tableoid, ctid)"
             } else {
                 "created_on"
@@ -1549,20 +2811,23 @@ pub mod article {
                                 
                                 lang, tags,
 
-                                unique_word_count 
+                                unique_word_count, difficulty
                                 
                                 FROM article 
                             WHERE 
                                 is_deleted = false AND
                                 is_system = false AND
+                                needs_segmentation = false AND
                                 (NOT is_private OR uploader_id = $1) AND
                                 COALESCE(lang = $2, TRUE) AND
-                                COALESCE(title &@~ $3, TRUE)
-                            ORDER BY {} DESC 
+                                COALESCE(title &@~ $3, TRUE) AND
+                                ($6::REAL IS NULL OR difficulty >= $6) AND
+                                ($7::REAL IS NULL OR difficulty <= $7)
+                            ORDER BY {}
                             LIMIT $5
                             OFFSET $4
                         "#,
-                        order_by_str
+                        get_article_order_by(&filter.sort, order_by_str)
                     )[..],
                 )
                 .await
@@ -1573,13 +2838,15 @@ pub mod article {
                     &statement,
                     &[
                         req_user_id,
-                        lang,
-                        search,
+                        &filter.lang,
+                        &filter.search,
                         offset,
                         match limit {
                             Some(limit) => limit,
                             None => &10i64,
                         },
+                        &filter.min_difficulty,
+                        &filter.max_difficulty,
                     ],
                 )
                 .await
@@ -205,6 +205,18 @@ pub mod user {
             .await
             .expect("Error connecting to the database");
 
+        if let Some(page_size) = json.page_size {
+            if !(lang::MIN_PAGE_SIZE..=lang::MAX_PAGE_SIZE).contains(&page_size) {
+                return user_res::get_invalid_page_size_error();
+            }
+        }
+
+        if let Some(ref page_unit) = json.page_unit {
+            if lang::PageUnit::parse(page_unit).is_none() {
+                return user_res::get_invalid_page_size_error();
+            }
+        }
+
         let result = db::user::update_user(
             &client,
             &auth_user.id,
@@ -224,11 +236,139 @@ pub mod user {
     pub mod data {
         use super::*;
 
+        /// Returns the language words are stored under and whether they're
+        /// written in simplified characters, which is the case for Chinese when
+        /// the user keeps one vocabulary for both scripts.
+        pub(super) async fn get_stored_lang(
+            client: &Client,
+            user_id: &i32,
+            language: &str,
+        ) -> Result<(String, bool), &'static str> {
+            if !lang::is_chinese(language) {
+                return Ok((language.to_string(), false));
+            }
+
+            match db::user::get_user_by_id(client, user_id).await? {
+                Some(user) if user.merge_chinese_scripts => {
+                    Ok((lang::SHARED_CHINESE_LANG.to_string(), true))
+                }
+                Some(_) => Ok((language.to_string(), false)),
+                None => Err("missing"),
+            }
+        }
+
+        /// Words are stored in lowercase, as articles count them. Inflected
+        /// words are stored under the lemma articles count them by, unless the
+        /// request asks to keep the word as it was written. Words apart make a
+        /// phrase, which is always stored under the key articles match it by.
+        pub(super) fn get_stored_word(
+            word: &str,
+            language: &str,
+            lemmatize: Option<bool>,
+            simplify: bool,
+        ) -> String {
+            let word = word.to_lowercase();
+
+            let phrase_key = if word.trim().contains(char::is_whitespace) {
+                lang::get_phrase_key(&word, language)
+            } else {
+                None
+            };
+
+            let word = if let Some(phrase_key) = phrase_key {
+                phrase_key
+            } else if lemmatize.unwrap_or(true) {
+                lang::get_lemma(&word, language).map_or(word, |lemma| lemma.to_lowercase())
+            } else {
+                word
+            };
+
+            if simplify {
+                lang::to_simplified(&word)
+            } else {
+                word
+            }
+        }
+
+        const MAX_LEARNING_LEVEL: i32 = 4;
+
+        /// Returns the level a word is stored with for a status, which only
+        /// learning words have, or `None` if the status or level is invalid.
+        fn get_status_level(status: &str, level: Option<i32>) -> Option<Option<i32>> {
+            match (status, level) {
+                ("learning", None) => Some(Some(1)),
+                ("learning", Some(level)) if (1..=MAX_LEARNING_LEVEL).contains(&level) => {
+                    Some(Some(level))
+                }
+                ("new", None) | ("known", None) | ("ignored", None) => Some(None),
+                _ => None,
+            }
+        }
+
+        const DEFAULT_WORD_DATA_LIMIT: i64 = 1000;
+        const MAX_WORD_DATA_LIMIT: i64 = 5000;
+
+        /// Returns a page of the user's word data, with the Chinese entries
+        /// merged into one vocabulary if the user shares it between scripts,
+        /// and whether there are more pages. Only the words of the languages
+        /// are fetched when they're given, and only those of the article when
+        /// it's given.
+        async fn get_merged_word_data(
+            client: &Client,
+            user_id: &i32,
+            language: Option<&str>,
+            article_id: Option<i32>,
+            offset: &i64,
+            limit: &i64,
+        ) -> Result<(models::db::UserWordData, bool), &'static str> {
+            let merge_chinese_scripts = match db::user::get_user_by_id(client, user_id).await? {
+                Some(user) => user.merge_chinese_scripts,
+                None => return Err("missing"),
+            };
+            let shared_langs = if merge_chinese_scripts {
+                lang::get_word_data_langs(lang::SHARED_CHINESE_LANG)
+            } else {
+                vec![]
+            };
+            let filter = models::db::WordDataFilter {
+                langs: language.map(lang::get_word_data_langs),
+                article_id,
+            };
+
+            let (mut data, has_more) = db::user::word_data::get_user_word_data(
+                client,
+                user_id,
+                &shared_langs,
+                offset,
+                limit,
+                &filter,
+            )
+            .await?;
+
+            if merge_chinese_scripts {
+                lang::merge_chinese_word_data(
+                    &mut data.word_status_data,
+                    &mut data.word_definition_data,
+                    &mut data.word_context_data,
+                );
+            }
+
+            Ok((data, has_more))
+        }
+
         #[get("/user/data/")]
         pub async fn get_user_word_data(
             db_pool: web::Data<Pool>,
+            query: web::Query<models::net::GetWordDataRequest>,
             auth_user: models::db::ClaimsUser,
         ) -> impl Responder {
+            let offset = util::get_default_offset(&query.offset);
+            let limit = query.limit.unwrap_or(DEFAULT_WORD_DATA_LIMIT);
+
+            if *offset < 0 || !(1..=MAX_WORD_DATA_LIMIT).contains(&limit) {
+                return user_res::get_invalid_word_data_page_error();
+            }
+
             let client: Client = match db_pool.get().await {
                 Ok(client) => client,
                 Err(err) => {
@@ -237,21 +377,69 @@ pub mod user {
                 }
             };
 
-            let result = db::user::word_data::get_user_word_data(&client, &auth_user.id).await;
-
-            match result {
-                Ok(data) => HttpResponse::Ok().json(models::net::GetWordDataResponse::new(data)),
+            match get_merged_word_data(
+                &client,
+                &auth_user.id,
+                query.lang.as_deref(),
+                query.article_id,
+                offset,
+                &limit,
+            )
+            .await
+            {
+                Ok((data, has_more)) => {
+                    HttpResponse::Ok().json(models::net::GetWordDataResponse::new(data, has_more))
+                }
                 Err(_) => user_res::get_fetch_data_error(),
             }
         }
 
+        /// Returns the article and sentence a word was marked as learning in,
+        /// if the request says where in an article the word is. An article the
+        /// user can't read, or an index past its words, leaves the word
+        /// without context.
+        async fn get_word_context(
+            client: &Client,
+            user_id: &i32,
+            json: &models::net::UpdateWordStatusRequest,
+        ) -> Result<Option<(i32, String)>, &'static str> {
+            let (article_id, word_index) = match (json.article_id, json.word_index) {
+                (Some(article_id), Some(word_index)) if json.status == "learning" => {
+                    (article_id, word_index)
+                }
+                _ => return Ok(None),
+            };
+
+            let sentence_data =
+                match db::article::get_article_sentence_data(client, &article_id, user_id).await? {
+                    Some(sentence_data) => sentence_data,
+                    None => return Ok(None),
+                };
+
+            let sentence = match (sentence_data.sentences, sentence_data.sentence_stops) {
+                (Some(sentences), Some(sentence_stops)) => {
+                    usize::try_from(word_index).ok().and_then(|word_index| {
+                        lang::get_word_sentence(&sentences, &sentence_stops, word_index)
+                    })
+                }
+                _ => None,
+            };
+
+            Ok(sentence.map(|sentence| (article_id, sentence)))
+        }
+
         #[put("/user/data/status/")]
         pub async fn update_word_status(
             db_pool: web::Data<Pool>,
             json: web::Json<models::net::UpdateWordStatusRequest>,
             auth_user: models::db::ClaimsUser,
         ) -> impl Responder {
-            let client: Client = match db_pool.get().await {
+            let level = match get_status_level(&json.status, json.level) {
+                Some(level) => level,
+                None => return user_res::get_invalid_word_status_error(),
+            };
+
+            let mut client: Client = match db_pool.get().await {
                 Ok(client) => client,
                 Err(err) => {
                     eprintln!("{}", err);
@@ -259,18 +447,67 @@ pub mod user {
                 }
             };
 
+            let (language, simplify) =
+                match get_stored_lang(&client, &auth_user.id, &json.lang).await {
+                    Ok(stored_lang) => stored_lang,
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        return user_res::get_update_word_status_error();
+                    }
+                };
+
+            let word = get_stored_word(&json.word, &json.lang, json.lemmatize, simplify);
+
+            let context = match get_word_context(&client, &auth_user.id, &json).await {
+                Ok(context) => context,
+                Err(_) => return user_res::get_update_word_status_error(),
+            };
+
+            let trans = match client.transaction().await {
+                Ok(trans) => trans,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_update_word_status_error();
+                }
+            };
+
             let result = db::user::word_data::update_word_status(
-                &client,
+                &trans,
                 &auth_user.id,
-                &json.lang,
-                &json.word,
+                &language,
+                &word,
                 &json.status,
+                &level,
+                &json.article_id,
             )
             .await;
 
-            match result {
+            if result.is_err() {
+                return user_res::get_update_word_status_error();
+            }
+
+            if let Some((article_id, sentence)) = context {
+                let result = db::user::word_data::add_word_context(
+                    &trans,
+                    &auth_user.id,
+                    &language,
+                    &word,
+                    &article_id,
+                    &sentence,
+                )
+                .await;
+
+                if result.is_err() {
+                    return user_res::get_update_word_status_error();
+                }
+            }
+
+            match trans.commit().await {
                 Ok(()) => get_success(),
-                Err(_) => user_res::get_update_word_status_error(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    user_res::get_update_word_status_error()
+                }
             }
         }
 
@@ -280,7 +517,12 @@ pub mod user {
             json: web::Json<models::net::BatchUpdateWordStatusRequest>,
             auth_user: models::db::ClaimsUser,
         ) -> impl Responder {
-            let client: Client = match db_pool.get().await {
+            let level = match get_status_level(&json.status, json.level) {
+                Some(level) => level,
+                None => return user_res::get_invalid_word_status_error(),
+            };
+
+            let mut client: Client = match db_pool.get().await {
                 Ok(client) => client,
                 Err(err) => {
                     eprintln!("{}", err);
@@ -288,18 +530,96 @@ pub mod user {
                 }
             };
 
+            let (language, simplify) =
+                match get_stored_lang(&client, &auth_user.id, &json.lang).await {
+                    Ok(stored_lang) => stored_lang,
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        return user_res::get_update_word_status_error();
+                    }
+                };
+
+            let words = json
+                .words
+                .iter()
+                .map(|word| get_stored_word(word, &json.lang, json.lemmatize, simplify))
+                .collect::<Vec<String>>();
+
+            let trans = match client.transaction().await {
+                Ok(trans) => trans,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_update_word_status_error();
+                }
+            };
+
             let result = db::user::word_data::batch_update_word_status(
-                &client,
+                &trans,
                 &auth_user.id,
-                &json.lang,
-                &json.words,
+                &language,
+                &words,
                 &json.status,
+                &level,
+                &json.article_id,
             )
             .await;
 
-            match result {
+            if result.is_err() {
+                return user_res::get_update_word_status_error();
+            }
+
+            match trans.commit().await {
                 Ok(()) => get_success(),
-                Err(_) => user_res::get_update_word_status_error(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    user_res::get_update_word_status_error()
+                }
+            }
+        }
+
+        const DEFAULT_TIMELINE_PERIODS: i64 = 30;
+        const MAX_TIMELINE_PERIODS: i64 = 366;
+
+        /// Returns how many words the user moved into each status per day, week
+        /// or month, by language, up to the current period.
+        #[get("/user/data/timeline/")]
+        pub async fn get_timeline(
+            db_pool: web::Data<Pool>,
+            query: web::Query<models::net::GetTimelineRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let period = query.period.as_deref().unwrap_or("day");
+            let period_count = query.periods.unwrap_or(DEFAULT_TIMELINE_PERIODS);
+
+            if !matches!(period, "day" | "week" | "month")
+                || !(1..=MAX_TIMELINE_PERIODS).contains(&period_count)
+            {
+                return user_res::get_invalid_timeline_error();
+            }
+
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_fetch_timeline_error();
+                }
+            };
+
+            let langs = query.lang.as_deref().map(lang::get_word_data_langs);
+            let result = db::user::word_data::get_timeline(
+                &client,
+                &auth_user.id,
+                langs.as_deref(),
+                period,
+                &period_count,
+            )
+            .await;
+
+            match result {
+                Ok(periods) => {
+                    HttpResponse::Ok().json(models::net::GetTimelineResponse::new(periods))
+                }
+                Err(_) => user_res::get_fetch_timeline_error(),
             }
         }
 
@@ -317,11 +637,22 @@ pub mod user {
                 }
             };
 
+            let (language, simplify) =
+                match get_stored_lang(&client, &auth_user.id, &json.lang).await {
+                    Ok(stored_lang) => stored_lang,
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        return user_res::get_update_word_definition_error();
+                    }
+                };
+
+            let word = get_stored_word(&json.word, &json.lang, json.lemmatize, simplify);
+
             let result = db::user::word_data::update_word_definition(
                 &client,
                 &auth_user.id,
-                &json.lang,
-                &json.word,
+                &language,
+                &word,
                 &json.definition,
             )
             .await;
@@ -419,90 +750,947 @@ pub mod user {
                 }
             };
 
-            let result = db::user::word_data::mark_article(
-                &client,
-                &auth_user.id,
-                &json.article_id,
-                &json.mark,
-            )
-            .await;
+            let result = db::user::word_data::mark_article(
+                &client,
+                &auth_user.id,
+                &json.article_id,
+                &json.mark,
+            )
+            .await;
+
+            match result {
+                Ok(()) => get_success(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    user_res::get_mark_article_error()
+                }
+            }
+        }
+
+        #[delete("/user/data/mark_article/")]
+        pub async fn delete_mark(
+            db_pool: web::Data<Pool>,
+            json: web::Json<models::net::DeleteMarkRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::delete_mark_error();
+                }
+            };
+
+            let result = db::user::word_data::delete_mark(
+                &client,
+                auth_user.id,
+                json.article_id,
+                json.index,
+            )
+            .await;
+
+            match result {
+                Ok(()) => get_success(),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    user_res::delete_mark_error()
+                }
+            }
+        }
+    }
+
+    pub mod dictionary {
+        use super::*;
+
+        const MAX_WORD_CHARS: usize = 100;
+        const MAX_TAG_CHARS: usize = 20;
+
+        // jieba dictionary lines are whitespace separated, so words can't contain any
+        fn is_valid_word(word: &models::net::AddDictionaryWordRequest) -> bool {
+            let word_chars = word.word.chars().count();
+
+            let is_valid_tag = match word.tag {
+                Some(ref tag) => {
+                    !tag.is_empty()
+                        && tag.chars().count() <= MAX_TAG_CHARS
+                        && !tag.chars().any(char::is_whitespace)
+                }
+                None => true,
+            };
+
+            word_chars > 0
+                && word_chars <= MAX_WORD_CHARS
+                && !word.word.chars().any(char::is_whitespace)
+                && !matches!(word.frequency, Some(frequency) if frequency <= 0)
+                && is_valid_tag
+        }
+
+        #[get("/user/dictionary/")]
+        pub async fn get_dictionary(
+            db_pool: web::Data<Pool>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_fetch_dictionary_error();
+                }
+            };
+
+            let result = db::user::dictionary::get_dictionary_words(&client, &auth_user.id).await;
+
+            match result {
+                Ok(words) => {
+                    HttpResponse::Ok().json(models::net::GetDictionaryResponse::new(words))
+                }
+                Err(_) => user_res::get_fetch_dictionary_error(),
+            }
+        }
+
+        #[post("/user/dictionary/")]
+        pub async fn add_dictionary_word(
+            db_pool: web::Data<Pool>,
+            json: web::Json<models::net::AddDictionaryWordRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            if !is_valid_word(&json) {
+                return user_res::get_invalid_dictionary_word_error();
+            }
+
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_update_dictionary_error();
+                }
+            };
+
+            let models::net::AddDictionaryWordRequest {
+                word,
+                frequency,
+                tag,
+            } = json.into_inner();
+
+            let result = db::user::dictionary::add_dictionary_word(
+                &client,
+                &auth_user.id,
+                &models::db::DictionaryWord {
+                    word,
+                    frequency,
+                    tag,
+                },
+            )
+            .await;
+
+            match result {
+                Ok(()) => get_success(),
+                Err(_) => user_res::get_update_dictionary_error(),
+            }
+        }
+
+        #[delete("/user/dictionary/")]
+        pub async fn remove_dictionary_word(
+            db_pool: web::Data<Pool>,
+            json: web::Json<models::net::RemoveDictionaryWordRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_update_dictionary_error();
+                }
+            };
+
+            let result =
+                db::user::dictionary::remove_dictionary_word(&client, &auth_user.id, &json.word)
+                    .await;
+
+            match result {
+                Ok(()) => get_success(),
+                Err("missing") => user_res::get_dictionary_word_missing_error(),
+                Err(_) => user_res::get_update_dictionary_error(),
+            }
+        }
+    }
+
+    pub mod phrase {
+        use super::*;
+
+        const MAX_PHRASE_CHARS: usize = 100;
+
+        fn get_user_phrase(json: models::net::PhraseRequest) -> Option<models::db::UserPhrase> {
+            if json.phrase.chars().count() > MAX_PHRASE_CHARS {
+                return None;
+            }
+
+            Some(models::db::UserPhrase {
+                phrase: lang::get_phrase_key(&json.phrase, &json.lang)?,
+                lang: lang::get_phrase_lang(&json.lang).to_string(),
+            })
+        }
+
+        #[get("/user/phrase/")]
+        pub async fn get_phrases(
+            db_pool: web::Data<Pool>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_fetch_phrases_error();
+                }
+            };
+
+            match db::user::phrase::get_phrases(&client, &auth_user.id).await {
+                Ok(phrases) => {
+                    HttpResponse::Ok().json(models::net::GetPhrasesResponse::new(phrases))
+                }
+                Err(_) => user_res::get_fetch_phrases_error(),
+            }
+        }
+
+        /// Adds a phrase to be found in the articles the user uploads from now
+        /// on, or segments again.
+        #[post("/user/phrase/")]
+        pub async fn add_phrase(
+            db_pool: web::Data<Pool>,
+            json: web::Json<models::net::PhraseRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let phrase = match get_user_phrase(json.into_inner()) {
+                Some(phrase) => phrase,
+                None => return user_res::get_invalid_phrase_error(),
+            };
+
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_update_phrases_error();
+                }
+            };
+
+            match db::user::phrase::add_phrase(&client, &auth_user.id, &phrase).await {
+                Ok(()) => get_success(),
+                Err(_) => user_res::get_update_phrases_error(),
+            }
+        }
+
+        #[delete("/user/phrase/")]
+        pub async fn remove_phrase(
+            db_pool: web::Data<Pool>,
+            json: web::Json<models::net::PhraseRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let phrase = match get_user_phrase(json.into_inner()) {
+                Some(phrase) => phrase,
+                None => return user_res::get_phrase_missing_error(),
+            };
+
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_update_phrases_error();
+                }
+            };
+
+            match db::user::phrase::remove_phrase(&client, &auth_user.id, &phrase).await {
+                Ok(()) => get_success(),
+                Err("missing") => user_res::get_phrase_missing_error(),
+                Err(_) => user_res::get_update_phrases_error(),
+            }
+        }
+    }
+
+    pub mod review {
+        use super::*;
+
+        const DEFAULT_REVIEW_LIMIT: i64 = 50;
+        const MAX_REVIEW_LIMIT: i64 = 200;
+
+        /// Returns the words of a language the user is learning that are due
+        /// for review, the longest overdue first.
+        #[get("/user/review/")]
+        pub async fn get_review_queue(
+            db_pool: web::Data<Pool>,
+            query: web::Query<models::net::GetReviewQueueRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let limit = query.limit.unwrap_or(DEFAULT_REVIEW_LIMIT);
+            if !(1..=MAX_REVIEW_LIMIT).contains(&limit) {
+                return user_res::get_invalid_review_limit_error();
+            }
+
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_fetch_review_queue_error();
+                }
+            };
+
+            let (language, _) =
+                match super::data::get_stored_lang(&client, &auth_user.id, &query.lang).await {
+                    Ok(stored_lang) => stored_lang,
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        return user_res::get_fetch_review_queue_error();
+                    }
+                };
+
+            let result =
+                db::user::review::get_due_words(&client, &auth_user.id, &language, &limit).await;
+
+            match result {
+                Ok(words) => {
+                    HttpResponse::Ok().json(models::net::GetReviewQueueResponse::new(words))
+                }
+                Err(_) => user_res::get_fetch_review_queue_error(),
+            }
+        }
+
+        /// Grades a review of a word and schedules its next one.
+        #[put("/user/review/")]
+        pub async fn review_word(
+            db_pool: web::Data<Pool>,
+            json: web::Json<models::net::ReviewWordRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            if !crate::review::is_valid_grade(json.grade) {
+                return user_res::get_invalid_grade_error();
+            }
+
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return user_res::get_review_word_error();
+                }
+            };
+
+            let (language, simplify) =
+                match super::data::get_stored_lang(&client, &auth_user.id, &json.lang).await {
+                    Ok(stored_lang) => stored_lang,
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        return user_res::get_review_word_error();
+                    }
+                };
+
+            let word =
+                super::data::get_stored_word(&json.word, &json.lang, json.lemmatize, simplify);
+
+            let schedule = match db::user::review::get_schedule(
+                &client,
+                &auth_user.id,
+                &language,
+                &word,
+            )
+            .await
+            {
+                Ok(schedule) => schedule,
+                Err("missing") => return user_res::get_review_word_missing_error(),
+                Err(_) => return user_res::get_review_word_error(),
+            };
+
+            let result = db::user::review::update_schedule(
+                &client,
+                &auth_user.id,
+                &language,
+                &word,
+                &crate::review::get_next_schedule(&schedule, json.grade),
+            )
+            .await;
+
+            match result {
+                Ok(word) => HttpResponse::Ok().json(models::net::ReviewWordResponse::new(word)),
+                Err("missing") => user_res::get_review_word_missing_error(),
+                Err(_) => user_res::get_review_word_error(),
+            }
+        }
+    }
+}
+
+pub mod article {
+    use super::*;
+
+    /// Returns the words of the user's own segmentation dictionary, which only
+    /// apply to the languages whose tokenizer supports custom words.
+    async fn get_custom_words(
+        client: &Client,
+        user_id: &i32,
+        language: &str,
+    ) -> Result<Vec<models::db::DictionaryWord>, &'static str> {
+        if !lang::is_chinese(language) {
+            return Ok(vec![]);
+        }
+
+        db::user::dictionary::get_dictionary_words(client, user_id).await
+    }
+
+    /// Returns the keys of the phrases the user added for the language, which
+    /// are found in their articles along with the bundled phrases.
+    async fn get_user_phrases(
+        client: &Client,
+        user_id: &i32,
+        language: &str,
+    ) -> Result<Vec<String>, &'static str> {
+        db::user::phrase::get_lang_phrases(client, user_id, lang::get_phrase_lang(language)).await
+    }
+
+    /// Builds the filter shared by the article lists, turning the search into
+    /// a query for the list language.
+    fn get_article_list_filter(
+        language: &Option<String>,
+        search: &Option<String>,
+        min_difficulty: Option<f32>,
+        max_difficulty: Option<f32>,
+        sort: &Option<String>,
+    ) -> Result<models::db::ArticleListFilter, HttpResponse> {
+        if !matches!(sort.as_deref(), None | Some("easiest") | Some("hardest")) {
+            return Err(article_res::get_invalid_sort_error());
+        }
+
+        let search_query_opt = match lang::get_or_query_string(search, language) {
+            Ok(search_query_opt) => search_query_opt,
+            Err(_) => return Err(article_res::get_unsupported_language_error()),
+        };
+
+        Ok(models::db::ArticleListFilter {
+            lang: language.clone(),
+            search: search_query_opt,
+            min_difficulty,
+            max_difficulty,
+            sort: sort.clone(),
+        })
+    }
+
+    /// Returns the languages whose words are matched by the simplified words
+    /// of Chinese articles, which are all the Chinese ones if the user shares
+    /// one vocabulary between scripts.
+    async fn get_shared_langs(client: &Client, user_id: &i32) -> Result<Vec<String>, &'static str> {
+        match db::user::get_user_by_id(client, user_id).await? {
+            Some(user) if user.merge_chinese_scripts => {
+                Ok(lang::get_word_data_langs(lang::SHARED_CHINESE_LANG))
+            }
+            Some(_) => Ok(vec![]),
+            None => Err("missing"),
+        }
+    }
+
+    /// Adds how much of each article's vocabulary the user already knows to
+    /// an article list.
+    async fn get_article_list_items(
+        client: &Client,
+        user_id: &i32,
+        articles: Vec<models::db::SimpleArticle>,
+    ) -> Result<Vec<models::db::ArticleListItem>, &'static str> {
+        let shared_langs = get_shared_langs(client, user_id).await?;
+        let article_ids = articles
+            .iter()
+            .map(|article| article.id)
+            .collect::<Vec<i32>>();
+        let comprehensions =
+            db::article::get_article_comprehension(client, user_id, &shared_langs, &article_ids)
+                .await?;
+
+        Ok(articles
+            .into_iter()
+            .map(|article| {
+                let (comprehension, new_word_count) = comprehensions
+                    .get(&article.id)
+                    .copied()
+                    .unwrap_or((100.0, 0));
+
+                models::db::ArticleListItem {
+                    article,
+                    comprehension,
+                    new_word_count,
+                }
+            })
+            .collect())
+    }
+
+    fn get_language_candidates(
+        detection: &Option<lang::LanguageDetection>,
+    ) -> Vec<models::net::LanguageCandidate> {
+        match detection {
+            Some(detection) => detection
+                .candidates
+                .iter()
+                .map(|(lang, confidence)| models::net::LanguageCandidate {
+                    lang: lang.to_string(),
+                    confidence: (confidence * 100.0).round() / 100.0,
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    fn compute_article_content_data(
+        content_text: &lang::ContentText,
+        language: &str,
+        dictionary_words: &[models::db::DictionaryWord],
+        user_phrases: &[String],
+    ) -> Result<models::db::ArticleContentData, &'static str> {
+        let custom_words = dictionary_words
+            .iter()
+            .map(|word| lang::CustomWord {
+                word: &word.word,
+                freq: word.frequency.map(|frequency| frequency as usize),
+            })
+            .collect::<Vec<lang::CustomWord>>();
+
+        let custom_tokenizer = lang::get_custom_tokenizer(language, &custom_words);
+        let tokenizer = match custom_tokenizer {
+            Some(ref tokenizer) => &**tokenizer,
+            None => lang::get_tokenizer(language)?,
+        };
+        let lemmatizer = lang::get_lemmatizer(language);
+
+        let content = &content_text.text[..];
+        let (words, base_forms) = lang::get_words_and_base_forms(content, tokenizer, lemmatizer);
+        let pinyin = lang::get_pinyin(&words[..], language);
+        let mut token_data = lang::get_token_data(content, &words[..], tokenizer);
+        let marks = lang::apply_markup(content_text, &mut token_data);
+        let lang::TokenData {
+            char_offsets,
+            byte_offsets,
+            token_types,
+            paragraph_indices,
+            block_types,
+        } = token_data;
+        let (unique_words, total_word_count, word_index_map, stop_word_map) =
+            lang::get_article_main_data(&words[..], &base_forms[..], &token_types[..]);
+        let phrases = lang::get_phrase_spans(
+            &words[..],
+            &base_forms[..],
+            &token_types[..],
+            language,
+            user_phrases,
+        );
+        let difficulty =
+            lang::get_difficulty(&words[..], &base_forms[..], &token_types[..], language);
+        let sentences_opt =
+            lang::get_sentences(content, &words[..], &paragraph_indices[..], tokenizer);
+
+        let (sentences, sentence_stops) = match sentences_opt {
+            Some((sentences, sentence_stops)) => (
+                Some(serde_json::to_value(sentences).unwrap()),
+                Some(sentence_stops),
+            ),
+            None => (None, None),
+        };
+
+        Ok(models::db::ArticleContentData {
+            words,
+            base_forms,
+            pinyin,
+
+            char_offsets,
+            byte_offsets,
+            token_types,
+            paragraph_indices,
+            block_types,
+            difficulty,
+
+            unique_words,
+            unique_word_count: i32::try_from(total_word_count).ok().unwrap(),
+
+            word_index_map,
+            stop_word_map,
+
+            sentences,
+            sentence_stops,
+
+            marks: serde_json::to_value(marks).unwrap(),
+            phrases: serde_json::to_value(phrases).unwrap(),
+        })
+    }
+
+    /// Takes the page size from the request, and what it leaves out from the
+    /// user's settings.
+    async fn get_page_size(
+        client: &Client,
+        user_id: &i32,
+        page_size: Option<i32>,
+        page_unit: &Option<String>,
+    ) -> Result<lang::PageSize, HttpResponse> {
+        let (size, unit) = match (page_size, page_unit) {
+            (Some(size), Some(unit)) => (size, unit.clone()),
+            _ => match db::user::get_user_by_id(client, user_id).await {
+                Ok(Some(user)) => (
+                    page_size.unwrap_or(user.page_size),
+                    page_unit.clone().unwrap_or(user.page_unit),
+                ),
+                _ => return Err(article_res::get_fetch_article_error()),
+            },
+        };
+
+        lang::PageSize::new(size, &unit).ok_or_else(article_res::get_invalid_page_size_error)
+    }
+
+    /// Splits the article into pages of the page size. The page starts are
+    /// cached for every size an article is read at, until its content changes.
+    async fn get_article_page_starts(
+        client: &Client,
+        article_id: &i32,
+        source: &models::db::ArticlePageSource,
+        page_size: lang::PageSize,
+    ) -> Result<Vec<i32>, &'static str> {
+        let page_unit = page_size.unit.as_str();
+        let cached_page_starts =
+            db::article::get_page_starts(client, article_id, &page_size.size, page_unit).await?;
+
+        if let Some(page_starts) = cached_page_starts {
+            return Ok(page_starts);
+        }
+
+        let page_starts = lang::get_page_starts(
+            &source.words[..],
+            &source.token_types[..],
+            source.sentence_stops.as_deref().unwrap_or(&[]),
+            &source.paragraph_indices[..],
+            &source.block_types[..],
+            page_size,
+        );
+
+        db::article::save_page_starts(
+            client,
+            article_id,
+            &page_size.size,
+            page_unit,
+            &page_starts[..],
+        )
+        .await?;
+
+        Ok(page_starts)
+    }
+
+    /// Segments an article again with its uploader's current dictionary and
+    /// phrases, replacing all the data computed from its content.
+    async fn resegment_article_content(
+        client: &mut Client,
+        article_id: i32,
+        user_id: i32,
+    ) -> Result<(), HttpResponse> {
+        let (content, language, content_format) =
+            match db::article::does_own_article(client, article_id, user_id).await {
+                Ok(article) => article,
+                Err(err) => {
+                    if err == "missing" {
+                        return Err(article_res::get_edit_article_missing_error());
+                    }
+
+                    eprintln!("{}", err);
+                    return Err(article_res::get_edit_article_error());
+                }
+            };
+        let content_text = match lang::get_content_text(&content, &content_format) {
+            Ok(content_text) => content_text,
+            Err(_) => return Err(article_res::get_edit_article_error()),
+        };
+        let detection = lang::detect_language(&content_text.text);
+
+        let dictionary_words = match get_custom_words(client, &user_id, &language).await {
+            Ok(dictionary_words) => dictionary_words,
+            Err(_) => return Err(article_res::get_edit_article_error()),
+        };
+        let user_phrases = match get_user_phrases(client, &user_id, &language).await {
+            Ok(user_phrases) => user_phrases,
+            Err(_) => return Err(article_res::get_edit_article_error()),
+        };
+
+        let models::db::ArticleContentData {
+            words,
+            base_forms,
+            pinyin,
+            char_offsets,
+            byte_offsets,
+            token_types,
+            paragraph_indices,
+            block_types,
+            difficulty,
+            unique_words,
+            unique_word_count,
+            word_index_map,
+            stop_word_map,
+            sentences,
+            sentence_stops,
+            marks,
+            phrases,
+        } = match compute_article_content_data(
+            &content_text,
+            &language,
+            &dictionary_words,
+            &user_phrases,
+        ) {
+            Ok(content_data) => content_data,
+            Err(_) => return Err(article_res::get_unsupported_language_error()),
+        };
+
+        let vocabulary = lang::get_vocabulary(&unique_words);
+        let simplified_vocabulary = lang::get_simplified_words(&vocabulary, &language);
+
+        let main_data = models::db::ArticleMainData {
+            content,
+            content_format,
+
+            word_count: i32::try_from(words.len()).ok().unwrap(),
+
+            base_forms,
+            pinyin,
+
+            char_offsets,
+            byte_offsets,
+            token_types,
+            paragraph_indices,
+            block_types,
+            difficulty,
+
+            detected_lang: detection
+                .as_ref()
+                .map(|detection| detection.lang.to_string()),
+            lang_confidence: detection.as_ref().map(|detection| detection.confidence),
+
+            unique_words,
+            unique_word_count,
+
+            word_index_map,
+            stop_word_map,
+
+            sentences,
+            sentence_stops,
+
+            marks,
+            phrases,
+        };
+
+        let update_metadata_opt = models::db::UpdateArticleMetadataOpt {
+            title: None,
+            author: None,
+            content_description: None,
+            language: None,
+            tags: None,
+            is_private: None,
+        };
+
+        let trans = match client.transaction().await {
+            Ok(trans) => trans,
+            Err(err) => {
+                eprintln!("{}", err);
+                return Err(article_res::get_edit_article_error());
+            }
+        };
+
+        if let Err(err) = db::article::edit_article(
+            &trans,
+            article_id,
+            user_id,
+            update_metadata_opt,
+            Some(main_data),
+            Some(words),
+        )
+        .await
+        {
+            eprintln!("{}", err);
+            return Err(article_res::get_edit_article_error());
+        }
+
+        if db::article::index_article_words(
+            &trans,
+            &article_id,
+            &vocabulary,
+            &simplified_vocabulary,
+        )
+        .await
+        .is_err()
+        {
+            return Err(article_res::get_edit_article_error());
+        }
+
+        if let Err(err) = trans.commit().await {
+            eprintln!("{}", err);
+            return Err(article_res::get_edit_article_error());
+        }
 
-            match result {
-                Ok(()) => get_success(),
-                Err(err) => {
-                    eprintln!("{}", err);
-                    user_res::get_mark_article_error()
-                }
+        Ok(())
+    }
+
+    /// Segments the articles the migrations left without token data, which
+    /// can only be computed here. Articles that fail are marked so they aren't
+    /// tried again, and stay unreadable until their uploader segments them.
+    pub async fn segment_migrated_articles(db_pool: Pool) {
+        let mut client: Client = match db_pool.get().await {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("{}", err);
+                return;
             }
-        }
+        };
 
-        #[delete("/user/data/mark_article/")]
-        pub async fn delete_mark(
-            db_pool: web::Data<Pool>,
-            json: web::Json<models::net::DeleteMarkRequest>,
-            auth_user: models::db::ClaimsUser,
-        ) -> impl Responder {
-            let client: Client = match db_pool.get().await {
-                Ok(client) => client,
-                Err(err) => {
-                    eprintln!("{}", err);
-                    return user_res::delete_mark_error();
-                }
-            };
+        let articles = match db::article::get_unsegmented_articles(&client).await {
+            Ok(articles) => articles,
+            Err(err) => {
+                eprintln!("{}", err);
+                return;
+            }
+        };
 
-            let result = db::user::word_data::delete_mark(
-                &client,
-                auth_user.id,
-                json.article_id,
-                json.index,
-            )
-            .await;
+        for (article_id, uploader_id) in articles {
+            if resegment_article_content(&mut client, article_id, uploader_id)
+                .await
+                .is_err()
+            {
+                eprintln!("Couldn't segment article {}", article_id);
 
-            match result {
-                Ok(()) => get_success(),
-                Err(err) => {
+                if let Err(err) = db::article::set_segmentation_failed(&client, &article_id).await {
                     eprintln!("{}", err);
-                    user_res::delete_mark_error()
                 }
             }
         }
     }
-}
 
-pub mod article {
-    use super::*;
+    async fn get_article_page_data(
+        client: &Client,
+        article_id: &i32,
+        page_size: lang::PageSize,
+    ) -> Result<serde_json::Value, &'static str> {
+        let source = match db::article::get_article_page_source(client, article_id).await? {
+            Some(source) => source,
+            None => return Err("missing"),
+        };
+        let page_starts = get_article_page_starts(client, article_id, &source, page_size).await?;
+        let marks: Vec<lang::Mark> = serde_json::from_value(source.marks).unwrap_or_default();
+
+        Ok(lang::get_page_data(
+            &source.words[..],
+            &source.paragraph_indices[..],
+            &source.block_types[..],
+            &marks[..],
+            &page_starts[..],
+        ))
+    }
 
-    fn compute_article_content_data(
-        content: &str,
-        language: &str,
-    ) -> models::db::ArticleContentData {
-        let words = lang::get_words_owned(content, language);
-        let (unique_words, total_word_count, word_index_map, stop_word_map) =
-            lang::get_article_main_data(&words[..]);
-        let sentences_opt = lang::get_sentences(content, &words[..]);
-        let pages = lang::get_pages(&sentences_opt);
+    /// Reads the page asked for by its index, or by the index of a word on
+    /// it. Only the index data of the words on the page is sent.
+    async fn get_article_page_response(
+        client: &Client,
+        article_id: i32,
+        query: &models::net::GetArticlePageRequest,
+        page_size: lang::PageSize,
+    ) -> HttpResponse {
+        let source = match db::article::get_article_page_source(client, &article_id).await {
+            Ok(Some(source)) => source,
+            Ok(None) => return article_res::get_article_not_found(),
+            Err(_) => return article_res::get_fetch_article_error(),
+        };
+        let page_starts =
+            match get_article_page_starts(client, &article_id, &source, page_size).await {
+                Ok(page_starts) => page_starts,
+                Err(_) => return article_res::get_fetch_article_error(),
+            };
 
-        let (sentences, sentence_stops) = match sentences_opt {
-            Some((sentences, sentence_stops)) => (
-                Some(serde_json::to_value(sentences).unwrap()),
-                Some(sentence_stops),
-            ),
-            None => (None, None),
+        let word_count = source.words.len();
+        let page_index = match (query.word_index, query.page) {
+            (Some(word_index), _) if word_index >= 0 && (word_index as usize) < word_count => {
+                lang::get_page_index(&page_starts[..], word_index)
+            }
+            (Some(_), _) => return article_res::get_page_not_found(),
+            (None, Some(page)) if page >= 0 => page as usize,
+            (None, Some(_)) => return article_res::get_page_not_found(),
+            (None, None) => 0,
         };
 
-        models::db::ArticleContentData {
-            words,
+        let marks: Vec<lang::Mark> = serde_json::from_value(source.marks).unwrap_or_default();
+        let page = match lang::get_page(
+            word_count,
+            &source.paragraph_indices[..],
+            &source.block_types[..],
+            &marks[..],
+            &page_starts[..],
+            page_index,
+        ) {
+            Some(page) => page,
+            None => return article_res::get_page_not_found(),
+        };
 
-            unique_words,
-            unique_word_count: i32::try_from(total_word_count).ok().unwrap(),
+        let index_data = match db::article::get_article_index_data(client, &article_id).await {
+            Ok(Some(index_data)) => index_data,
+            Ok(None) => return article_res::get_article_not_found(),
+            Err(_) => return article_res::get_fetch_article_error(),
+        };
 
-            word_index_map,
-            stop_word_map,
+        // the token data is sliced by the words, so it can't be any shorter
+        let has_token_data = index_data.base_forms.len() == word_count
+            && source.token_types.len() == word_count
+            && index_data
+                .pinyin
+                .as_ref()
+                .map_or(true, |pinyin| pinyin.len() == word_count);
+        if !has_token_data {
+            eprintln!("Article {} has to be segmented again", article_id);
+            return article_res::get_article_unsegmented_error();
+        }
 
-            sentences,
-            sentence_stops,
+        let (word_index_map, stop_word_map) = lang::get_page_word_maps(
+            &index_data.word_index_map,
+            &index_data.stop_word_map,
+            page.start,
+            page.end,
+        );
+
+        let phrases: Vec<lang::PhraseSpan> =
+            serde_json::from_value(index_data.phrases).unwrap_or_default();
+        let lang::Page {
+            start,
+            end,
+            blocks,
+            marks,
+        } = page;
+        let mut words = source.words;
+        let mut base_forms = index_data.base_forms;
+        let mut token_types = source.token_types;
+
+        HttpResponse::Ok().json(models::net::GetArticlePageResponse::new(
+            models::db::ArticlePage {
+                article_id,
+                page_index: page_index as i32,
+                page_count: page_starts.len() as i32,
+                start: start as i32,
+
+                words: words.drain(start..end).collect(),
+                base_forms: base_forms.drain(start..end).collect(),
+                pinyin: index_data
+                    .pinyin
+                    .map(|mut pinyin| pinyin.drain(start..end).collect()),
+                token_types: token_types.drain(start..end).collect(),
+
+                blocks,
+                marks: serde_json::to_value(marks).unwrap(),
+                phrases: serde_json::to_value(lang::get_page_phrases(&phrases, start, end))
+                    .unwrap(),
 
-            page_data: pages,
+                word_index_map,
+                stop_word_map,
+            },
+        ))
+    }
+
+    async fn get_full_article_response(
+        client: &Client,
+        article: models::db::ReadArticle,
+        page_size: lang::PageSize,
+    ) -> HttpResponse {
+        match get_article_page_data(client, &article.id, page_size).await {
+            Ok(page_data) => HttpResponse::Ok().json(models::net::GetFullArticleResponse::new(
+                models::db::FullArticle { article, page_data },
+            )),
+            Err(_) => article_res::get_fetch_article_error(),
         }
     }
 
@@ -527,19 +1715,69 @@ pub mod article {
             language,
             tags,
             content,
+            content_format,
             is_private,
         } = json.0;
 
+        let content_format = content_format.unwrap_or_else(|| String::from("plain"));
+        let content_text = match lang::get_content_text(&content, &content_format) {
+            Ok(content_text) => content_text,
+            Err(_) => return article_res::get_invalid_content_format_error(),
+        };
+
+        let detection = lang::detect_language(&content_text.text);
+
+        let language = match (language, &detection) {
+            (Some(language), _) => language,
+            (None, Some(detection)) if detection.confidence >= lang::MIN_DETECTION_CONFIDENCE => {
+                detection.lang.to_string()
+            }
+            (None, _) => {
+                return article_res::get_language_undetected_error(get_language_candidates(
+                    &detection,
+                ))
+            }
+        };
+
+        let dictionary_words = match get_custom_words(&client, &auth_user.id, &language).await {
+            Ok(dictionary_words) => dictionary_words,
+            Err(_) => return article_res::get_create_article_error(),
+        };
+        let user_phrases = match get_user_phrases(&client, &auth_user.id, &language).await {
+            Ok(user_phrases) => user_phrases,
+            Err(_) => return article_res::get_create_article_error(),
+        };
+
         let models::db::ArticleContentData {
             words,
+            base_forms,
+            pinyin,
+            char_offsets,
+            byte_offsets,
+            token_types,
+            paragraph_indices,
+            block_types,
+            difficulty,
             unique_words,
             unique_word_count,
             word_index_map,
             stop_word_map,
             sentences,
             sentence_stops,
-            page_data,
-        } = compute_article_content_data(&content[..], &language[..]);
+            marks,
+            phrases,
+        } = match compute_article_content_data(
+            &content_text,
+            &language[..],
+            &dictionary_words,
+            &user_phrases,
+        ) {
+            Ok(content_data) => content_data,
+            Err(_) => return article_res::get_unsupported_language_error(),
+        };
+
+        let vocabulary = lang::get_vocabulary(&unique_words);
+        let simplified_vocabulary = lang::get_simplified_words(&vocabulary, &language);
 
         let trans = match client.transaction().await {
             Ok(trans) => trans,
@@ -564,9 +1802,25 @@ pub mod article {
             },
             models::db::ArticleMainData {
                 content,
+                content_format,
 
                 word_count: i32::try_from(words.len()).ok().unwrap(),
 
+                base_forms,
+                pinyin,
+
+                char_offsets,
+                byte_offsets,
+                token_types,
+                paragraph_indices,
+                block_types,
+                difficulty,
+
+                detected_lang: detection
+                    .as_ref()
+                    .map(|detection| detection.lang.to_string()),
+                lang_confidence: detection.as_ref().map(|detection| detection.confidence),
+
                 unique_words,
                 unique_word_count,
 
@@ -576,7 +1830,8 @@ pub mod article {
                 sentences,
                 sentence_stops,
 
-                page_data,
+                marks,
+                phrases,
             },
             words,
         )
@@ -588,6 +1843,18 @@ pub mod article {
 
         let article = result.unwrap();
 
+        if db::article::index_article_words(
+            &trans,
+            &article.id,
+            &vocabulary,
+            &simplified_vocabulary,
+        )
+        .await
+        .is_err()
+        {
+            return article_res::get_create_article_error();
+        }
+
         let save_result =
             db::article::user::user_save_article(&trans, &auth_user.id, &article.id).await;
 
@@ -623,9 +1890,9 @@ pub mod article {
             ..
         } = json.0; 
 
-        let (content_original, language_original) =
+        let (content_original, language_original, content_format_original) =
             match db::article::does_own_article(&client, article_id, auth_user.id).await {
-                Ok((content, language)) => (content, language),
+                Ok(article) => article,
                 Err(err) => {
                     if err == "missing" {
                         return article_res::get_edit_article_missing_error();
@@ -638,33 +1905,88 @@ pub mod article {
 
         let mut main_data_opt: Option<models::db::ArticleMainData> = None;
         let mut words_opt: Option<Vec<String>> = None;
+        let mut vocabulary_opt: Option<(Vec<String>, Option<Vec<String>>)> = None;
 
-        if json.content.is_some() || json.language.is_some() {
+        if json.content.is_some() || json.language.is_some() || json.content_format.is_some() {
             let models::net::EditArticleRequest {
                 content: content_opt,
+                content_format: content_format_opt,
                 language: ref language_opt,
                 ..
             } = json.0;
 
             let content = content_opt.unwrap_or(content_original);
+            let content_format = content_format_opt.unwrap_or(content_format_original);
+            let content_text = match lang::get_content_text(&content, &content_format) {
+                Ok(content_text) => content_text,
+                Err(_) => return article_res::get_invalid_content_format_error(),
+            };
+
+            let detection = lang::detect_language(&content_text.text);
             let language = language_opt.as_ref().unwrap_or(&language_original);
 
+            let dictionary_words = match get_custom_words(&client, &auth_user.id, language).await {
+                Ok(dictionary_words) => dictionary_words,
+                Err(_) => return article_res::get_edit_article_error(),
+            };
+            let user_phrases = match get_user_phrases(&client, &auth_user.id, language).await {
+                Ok(user_phrases) => user_phrases,
+                Err(_) => return article_res::get_edit_article_error(),
+            };
+
             let models::db::ArticleContentData {
                 words,
+                base_forms,
+                pinyin,
+                char_offsets,
+                byte_offsets,
+                token_types,
+                paragraph_indices,
+                block_types,
+                difficulty,
                 unique_words,
                 unique_word_count,
                 word_index_map,
                 stop_word_map,
                 sentences,
                 sentence_stops,
-                page_data,
-            } = compute_article_content_data(&content[..], &language[..]);
+                marks,
+                phrases,
+            } = match compute_article_content_data(
+                &content_text,
+                language,
+                &dictionary_words,
+                &user_phrases,
+            ) {
+                Ok(content_data) => content_data,
+                Err(_) => return article_res::get_unsupported_language_error(),
+            };
+
+            let vocabulary = lang::get_vocabulary(&unique_words);
+            let simplified_vocabulary = lang::get_simplified_words(&vocabulary, language);
+            vocabulary_opt = Some((vocabulary, simplified_vocabulary));
 
             main_data_opt = Some(models::db::ArticleMainData {
                 content,
+                content_format,
 
                 word_count: i32::try_from(words.len()).ok().unwrap(),
 
+                base_forms,
+                pinyin,
+
+                char_offsets,
+                byte_offsets,
+                token_types,
+                paragraph_indices,
+                block_types,
+                difficulty,
+
+                detected_lang: detection
+                    .as_ref()
+                    .map(|detection| detection.lang.to_string()),
+                lang_confidence: detection.as_ref().map(|detection| detection.confidence),
+
                 unique_words,
                 unique_word_count,
 
@@ -674,7 +1996,8 @@ pub mod article {
                 sentences,
                 sentence_stops,
 
-                page_data,
+                marks,
+                phrases,
             });
 
             words_opt = Some(words);
@@ -721,6 +2044,20 @@ pub mod article {
             return article_res::get_edit_article_error();
         }
 
+        if let Some((vocabulary, simplified_vocabulary)) = vocabulary_opt {
+            if db::article::index_article_words(
+                &trans,
+                &article_id,
+                &vocabulary,
+                &simplified_vocabulary,
+            )
+            .await
+            .is_err()
+            {
+                return article_res::get_edit_article_error();
+            }
+        }
+
         if let Err(err) = trans.commit().await {
             eprintln!("{}", err);
             return article_res::get_edit_article_error();
@@ -729,6 +2066,67 @@ pub mod article {
         get_success()
     }
 
+    const DEFAULT_MIN_COMPREHENSION: f32 = 90.0;
+    const DEFAULT_MAX_COMPREHENSION: f32 = 98.0;
+
+    #[get("/article/recommended/")]
+    pub async fn get_recommended_articles(
+        db_pool: web::Data<Pool>,
+        query: web::Query<models::net::GetRecommendedArticlesRequest>,
+        auth_user: models::db::ClaimsUser,
+    ) -> impl Responder {
+        let client: Client = match db_pool.get().await {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("{}", err);
+                return article_res::get_fetch_articles_error();
+            }
+        };
+
+        if let Some(ref language) = query.lang {
+            if lang::get_tokenizer(language).is_err() {
+                return article_res::get_unsupported_language_error();
+            }
+        }
+
+        let min_comprehension = query.min_comprehension.unwrap_or(DEFAULT_MIN_COMPREHENSION);
+        let max_comprehension = query.max_comprehension.unwrap_or(DEFAULT_MAX_COMPREHENSION);
+
+        if min_comprehension < 0.0
+            || max_comprehension > 100.0
+            || min_comprehension > max_comprehension
+        {
+            return article_res::get_invalid_comprehension_error();
+        }
+
+        let shared_langs = match get_shared_langs(&client, &auth_user.id).await {
+            Ok(shared_langs) => shared_langs,
+            Err(_) => return article_res::get_fetch_articles_error(),
+        };
+
+        let result = db::article::get_recommended_article_list(
+            &client,
+            &auth_user.id,
+            &shared_langs,
+            util::get_default_offset(&query.offset),
+            &query.limit,
+            &models::db::RecommendationFilter {
+                lang: query.lang.clone(),
+                tag: query.tag.clone(),
+                min_comprehension,
+                max_comprehension,
+            },
+        )
+        .await;
+
+        match result {
+            Ok(articles) => {
+                HttpResponse::Ok().json(models::net::GetArticlesResponse::new(articles))
+            }
+            Err(_) => article_res::get_fetch_articles_error(),
+        }
+    }
+
     pub mod system {
         use super::*;
 
@@ -736,7 +2134,7 @@ pub mod article {
         pub async fn get_articles(
             db_pool: web::Data<Pool>,
             query: web::Query<models::net::GetArticlesRequest>,
-            _: models::db::ClaimsUser,
+            auth_user: models::db::ClaimsUser,
         ) -> impl Responder {
             let client: Client = match db_pool.get().await {
                 Ok(client) => client,
@@ -748,18 +2146,31 @@ pub mod article {
 
             let offset = util::get_default_offset(&query.offset);
 
-            let search_query_opt = lang::get_or_query_string(&query.search, &query.lang);
+            let filter = match get_article_list_filter(
+                &query.lang,
+                &query.search,
+                query.min_difficulty,
+                query.max_difficulty,
+                &query.sort,
+            ) {
+                Ok(filter) => filter,
+                Err(res) => return res,
+            };
 
             let result = db::article::system::get_system_article_list(
                 &client,
                 offset,
-                &query.lang,
-                &search_query_opt,
                 &query.limit,
+                &filter,
             )
             .await;
 
-            match result {
+            let articles = match result {
+                Ok(articles) => articles,
+                Err(_) => return article_res::get_fetch_articles_error(),
+            };
+
+            match get_article_list_items(&client, &auth_user.id, articles).await {
                 Ok(articles) => {
                     HttpResponse::Ok().json(models::net::GetArticlesResponse::new(articles))
                 }
@@ -771,7 +2182,8 @@ pub mod article {
         pub async fn get_full_article(
             db_pool: web::Data<Pool>,
             web::Path(article_id): web::Path<i32>,
-            _: models::db::ClaimsUser,
+            query: web::Query<models::net::GetFullArticleRequest>,
+            auth_user: models::db::ClaimsUser,
         ) -> impl Responder {
             let client: Client = match db_pool.get().await {
                 Ok(client) => client,
@@ -781,18 +2193,62 @@ pub mod article {
                 }
             };
 
+            let page_size = match get_page_size(
+                &client,
+                &auth_user.id,
+                query.page_size,
+                &query.page_unit,
+            )
+            .await
+            {
+                Ok(page_size) => page_size,
+                Err(res) => return res,
+            };
+
             let result = db::article::system::get_system_article(&client, &article_id).await;
 
             match result {
                 Ok(article_opt) => match article_opt {
-                    Some(article) => {
-                        HttpResponse::Ok().json(models::net::GetFullArticleResponse::new(article))
-                    }
+                    Some(article) => get_full_article_response(&client, article, page_size).await,
                     None => article_res::get_article_not_found(),
                 },
                 Err(_) => article_res::get_fetch_article_error(),
             }
         }
+
+        #[get("/article/system/single/{article_id}/page/")]
+        pub async fn get_article_page(
+            db_pool: web::Data<Pool>,
+            web::Path(article_id): web::Path<i32>,
+            query: web::Query<models::net::GetArticlePageRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return article_res::get_fetch_article_error();
+                }
+            };
+
+            let page_size = match get_page_size(
+                &client,
+                &auth_user.id,
+                query.page_size,
+                &query.page_unit,
+            )
+            .await
+            {
+                Ok(page_size) => page_size,
+                Err(res) => return res,
+            };
+
+            match db::article::system::is_system_article(&client, &article_id).await {
+                Ok(true) => get_article_page_response(&client, article_id, &query, page_size).await,
+                Ok(false) => article_res::get_article_not_found(),
+                Err(_) => article_res::get_fetch_article_error(),
+            }
+        }
     }
 
     pub mod user {
@@ -821,20 +2277,33 @@ pub mod article {
 
             let offset = util::get_default_offset(&query.offset);
 
-            let search_query_opt = lang::get_or_query_string(&query.search, &query.lang);
+            let filter = match get_article_list_filter(
+                &query.lang,
+                &query.search,
+                query.min_difficulty,
+                query.max_difficulty,
+                &query.sort,
+            ) {
+                Ok(filter) => filter,
+                Err(res) => return res,
+            };
 
             let result = db::article::user::get_user_uploaded_article_list(
                 &client,
                 &req_user_id,
                 &target_user_id,
                 offset,
-                &query.lang,
-                &search_query_opt,
                 &query.limit,
+                &filter,
             )
             .await;
 
-            match result {
+            let articles = match result {
+                Ok(articles) => articles,
+                Err(_) => return article_res::get_fetch_articles_error(),
+            };
+
+            match get_article_list_items(&client, &auth_user.id, articles).await {
                 Ok(articles) => {
                     HttpResponse::Ok().json(models::net::GetArticlesResponse::new(articles))
                 }
@@ -860,19 +2329,32 @@ pub mod article {
 
             let offset = util::get_default_offset(&query.offset);
 
-            let search_query_opt = lang::get_or_query_string(&query.search, &query.lang);
+            let filter = match get_article_list_filter(
+                &query.lang,
+                &query.search,
+                query.min_difficulty,
+                query.max_difficulty,
+                &query.sort,
+            ) {
+                Ok(filter) => filter,
+                Err(res) => return res,
+            };
 
             let result = db::article::user::get_all_user_uploaded_article_list(
                 &client,
                 req_user_id,
                 offset,
-                &query.lang,
-                &search_query_opt,
                 &query.limit,
+                &filter,
             )
             .await;
 
-            match result {
+            let articles = match result {
+                Ok(articles) => articles,
+                Err(_) => return article_res::get_fetch_articles_error(),
+            };
+
+            match get_article_list_items(&client, &auth_user.id, articles).await {
                 Ok(articles) => {
                     HttpResponse::Ok().json(models::net::GetArticlesResponse::new(articles))
                 }
@@ -884,6 +2366,7 @@ pub mod article {
         pub async fn get_full_article(
             db_pool: web::Data<Pool>,
             web::Path(article_id): web::Path<i32>,
+            query: web::Query<models::net::GetFullArticleRequest>,
             auth_user: models::db::ClaimsUser,
         ) -> impl Responder {
             let client: Client = match db_pool.get().await {
@@ -894,20 +2377,67 @@ pub mod article {
                 }
             };
 
+            let page_size = match get_page_size(
+                &client,
+                &auth_user.id,
+                query.page_size,
+                &query.page_unit,
+            )
+            .await
+            {
+                Ok(page_size) => page_size,
+                Err(res) => return res,
+            };
+
             let result =
                 db::article::user::get_user_article(&client, &article_id, &auth_user.id).await;
 
             match result {
                 Ok(article_opt) => match article_opt {
-                    Some(article) => {
-                        HttpResponse::Ok().json(models::net::GetFullArticleResponse::new(article))
-                    }
+                    Some(article) => get_full_article_response(&client, article, page_size).await,
                     None => article_res::get_article_not_found(),
                 },
                 Err(_) => article_res::get_fetch_article_error(),
             }
         }
 
+        #[get("/article/user/single/{article_id}/page/")]
+        pub async fn get_article_page(
+            db_pool: web::Data<Pool>,
+            web::Path(article_id): web::Path<i32>,
+            query: web::Query<models::net::GetArticlePageRequest>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return article_res::get_fetch_article_error();
+                }
+            };
+
+            let page_size = match get_page_size(
+                &client,
+                &auth_user.id,
+                query.page_size,
+                &query.page_unit,
+            )
+            .await
+            {
+                Ok(page_size) => page_size,
+                Err(res) => return res,
+            };
+
+            let result =
+                db::article::user::can_read_article(&client, &article_id, &auth_user.id).await;
+
+            match result {
+                Ok(true) => get_article_page_response(&client, article_id, &query, page_size).await,
+                Ok(false) => article_res::get_article_not_found(),
+                Err(_) => article_res::get_fetch_article_error(),
+            }
+        }
+
         #[delete("/article/user/single/{article_id}/")]
         pub async fn delete_article(
             db_pool: web::Data<Pool>,
@@ -931,6 +2461,27 @@ pub mod article {
             }
         }
 
+        /// Segments the article again with the uploader's current dictionary.
+        #[post("/article/user/single/{article_id}/segment/")]
+        pub async fn resegment_article(
+            db_pool: web::Data<Pool>,
+            web::Path(article_id): web::Path<i32>,
+            auth_user: models::db::ClaimsUser,
+        ) -> impl Responder {
+            let mut client: Client = match db_pool.get().await {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return article_res::get_edit_article_error();
+                }
+            };
+
+            match resegment_article_content(&mut client, article_id, auth_user.id).await {
+                Ok(()) => get_success(),
+                Err(response) => response,
+            }
+        }
+
         #[get("/article/user/saved/list/")]
         pub async fn get_saved_article_list(
             db_pool: web::Data<Pool>,
@@ -947,19 +2498,32 @@ pub mod article {
 
             let offset = util::get_default_offset(&query.offset);
 
-            let search_query_opt = lang::get_or_query_string(&query.search, &query.lang);
+            let filter = match get_article_list_filter(
+                &query.lang,
+                &query.search,
+                query.min_difficulty,
+                query.max_difficulty,
+                &query.sort,
+            ) {
+                Ok(filter) => filter,
+                Err(res) => return res,
+            };
 
             let result = db::article::user::get_user_saved_article_list(
                 &client,
                 &auth_user.id,
                 offset,
-                &query.lang,
-                &search_query_opt,
                 &query.limit,
+                &filter,
             )
             .await;
 
-            match result {
+            let articles = match result {
+                Ok(articles) => articles,
+                Err(_) => return article_res::get_fetch_articles_error(),
+            };
+
+            match get_article_list_items(&client, &auth_user.id, articles).await {
                 Ok(articles) => {
                     HttpResponse::Ok().json(models::net::GetArticlesResponse::new(articles))
                 }
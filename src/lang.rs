@@ -1,98 +1,920 @@
-use jieba_rs::Jieba;
+mod chinese;
+mod detect;
+mod difficulty;
+mod english;
+mod european;
+mod japanese;
+mod korean;
+mod lemmatizer;
+mod markdown;
+mod morphemes;
+mod phrases;
+mod pinyin;
+mod sentence;
+
+use chinese::ChineseTokenizer;
+use english::EnglishTokenizer;
+use japanese::JapaneseTokenizer;
+use korean::KoreanTokenizer;
 use lazy_static::lazy_static;
+use lemmatizer::Lemmatizer;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
-use unicode_segmentation::UnicodeSegmentation;
 
-pub fn get_words_owned(text: &str, lang: &str) -> Vec<String> {
-    match lang {
-        "en" => get_words_english_owned(text),
-        "zh" | "zh-CN" | "zh-TW" => get_words_chinese_owned(text),
-        _ => panic!("Got unsupported language for get_words: {}", text),
-    }
+/// A word of the text along with the dictionary form it should be counted
+/// under, when that differs from the word itself.
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub base_form: Option<String>,
 }
 
-pub fn get_words_slice<'a>(text: &'a str, lang: &str) -> Vec<&'a str> {
-    match lang {
-        "en" => get_words_english_slice(text),
-        "zh" | "zh-CN" | "zh-TW" => get_words_chinese_slice(text),
-        _ => panic!("Got unsupported language for get_words: {}", text),
+/// Splits the text of a single language into words and sentences.
+///
+/// Every slice returned by `get_words` and `get_sentences` must borrow from
+/// the given text and, concatenated in order, cover all of it.
+pub trait Tokenizer: Send + Sync {
+    fn get_words<'a>(&self, text: &'a str) -> Vec<&'a str>;
+
+    fn get_tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        self.get_words(text)
+            .into_iter()
+            .map(|text| Token {
+                text,
+                base_form: None,
+            })
+            .collect::<Vec<Token>>()
+    }
+
+    fn get_sentences<'a>(&self, text: &'a str) -> Vec<&'a str>;
+
+    /// Whether the dictionary the tokenizer segments with could be loaded.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Returns the form a single word marked by a user should be stored
+    /// under, if it isn't the word itself.
+    fn get_base_form(&self, _word: &str) -> Option<String> {
+        None
+    }
+
+    fn is_punctuation(&self, word: &str) -> bool {
+        STOP_CHARS.contains(word)
+    }
+
+    /// Whether a word of another script is a foreign word in the text, like
+    /// the Latin letters of a brand name in Chinese.
+    fn is_foreign_word(&self, _word: &str) -> bool {
+        false
+    }
+
+    /// Whether a word is capitalized the way a name is, where capitals set
+    /// names apart from other words.
+    fn is_capitalized(&self, word: &str) -> bool {
+        is_capitalized(word)
     }
 }
 
-fn get_words_english_slice(text: &str) -> Vec<&str> {
-    text.split_word_bounds().collect::<Vec<&str>>()
+fn is_capitalized(word: &str) -> bool {
+    matches!(word.chars().next(), Some(c) if c.is_uppercase())
 }
 
-fn get_words_english_owned(text: &str) -> Vec<String> {
-    text.split_word_bounds()
-        .map(|slice| slice.to_string())
-        .collect::<Vec<String>>()
+// a word of Latin letters, as in iPhone or e-mail
+fn is_latin_word(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_alphabetic())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-'’.".contains(c))
 }
 
+static ENGLISH: EnglishTokenizer = EnglishTokenizer;
+static CHINESE: ChineseTokenizer = ChineseTokenizer::new();
+static JAPANESE: JapaneseTokenizer = JapaneseTokenizer;
+static KOREAN: KoreanTokenizer = KoreanTokenizer;
+
 lazy_static! {
-    static ref JIEBA: Jieba = Jieba::new();
+    static ref TOKENIZERS: HashMap<&'static str, &'static dyn Tokenizer> = {
+        let mut tokenizers: HashMap<&'static str, &'static dyn Tokenizer> = HashMap::new();
+        tokenizers.insert("en", &ENGLISH);
+        tokenizers.insert("zh", &CHINESE);
+        tokenizers.insert("zh-CN", &CHINESE);
+        tokenizers.insert("zh-TW", &CHINESE);
+        tokenizers.insert("ja", &JAPANESE);
+        tokenizers.insert("ko", &KOREAN);
+        tokenizers.insert("fr", &european::FRENCH);
+        tokenizers.insert("it", &european::ITALIAN);
+        tokenizers.insert("es", &european::SPANISH);
+        tokenizers.insert("pt", &european::PORTUGUESE);
+        tokenizers.insert("de", &european::GERMAN);
+        tokenizers
+    };
+    static ref LEMMATIZERS: HashMap<&'static str, &'static dyn Lemmatizer> = {
+        let mut lemmatizers: HashMap<&'static str, &'static dyn Lemmatizer> = HashMap::new();
+        lemmatizers.insert("en", &*lemmatizer::ENGLISH);
+        lemmatizers
+    };
+}
+
+pub fn get_tokenizer(lang: &str) -> Result<&'static dyn Tokenizer, &'static str> {
+    match TOKENIZERS.get(lang) {
+        Some(tokenizer) if tokenizer.is_available() => Ok(*tokenizer),
+        Some(_) => Err("unavailable"),
+        None => Err("unsupported"),
+    }
+}
+
+/// A word a user added to their own segmentation dictionary, with the
+/// frequency jieba should give it.
+pub struct CustomWord<'a> {
+    pub word: &'a str,
+    pub freq: Option<usize>,
+}
+
+/// Returns a tokenizer that also knows the user's own words, when the language
+/// supports custom dictionaries and the user has added any.
+pub fn get_custom_tokenizer(lang: &str, words: &[CustomWord]) -> Option<Box<dyn Tokenizer>> {
+    if !is_chinese(lang) || words.is_empty() {
+        return None;
+    }
+
+    Some(Box::new(ChineseTokenizer::with_custom_words(words)))
+}
+
+/// Languages without a lemmatizer only count words under the base forms
+/// their tokenizer finds.
+pub fn get_lemmatizer(lang: &str) -> Option<&'static dyn Lemmatizer> {
+    LEMMATIZERS.get(lang).copied()
+}
+
+pub fn get_base_form(word: &str, lang: &str) -> Option<String> {
+    get_tokenizer(lang).ok()?.get_base_form(word)
+}
+
+/// Returns the lemma a single word is counted under in articles, if it isn't
+/// the word itself.
+pub fn get_lemma(word: &str, lang: &str) -> Option<String> {
+    get_base_form(word, lang).or_else(|| get_lemmatizer(lang)?.get_lemma(word))
+}
+
+const CHINESE_LANGS: &[&str] = &["zh", "zh-CN", "zh-TW"];
+
+/// The language Chinese words are stored under, in simplified characters, by
+/// users who share one vocabulary between both scripts.
+pub const SHARED_CHINESE_LANG: &str = "zh";
+
+pub fn is_chinese(lang: &str) -> bool {
+    CHINESE_LANGS.contains(&lang)
+}
+
+pub fn to_simplified(text: &str) -> String {
+    chinese::to_simplified(text)
+}
+
+/// Returns the word written in both scripts, simplified first.
+pub fn get_script_variants(word: &str) -> Vec<String> {
+    let simplified = chinese::to_simplified(word);
+    let traditional = chinese::to_traditional(&simplified);
+
+    if traditional == simplified {
+        vec![simplified]
+    } else {
+        vec![simplified, traditional]
+    }
+}
+
+/// Returns the languages whose words are fetched for a language. Every Chinese
+/// language is needed for Chinese, since they may share one vocabulary.
+pub fn get_word_data_langs(lang: &str) -> Vec<String> {
+    if is_chinese(lang) {
+        CHINESE_LANGS.iter().map(|lang| lang.to_string()).collect()
+    } else {
+        vec![lang.to_string()]
+    }
+}
+
+/// Folds the Chinese entries of a user's word data into one vocabulary, listing
+/// every word in both scripts under each Chinese language code so that it's
+/// found whichever script an article is written in. The contexts of a word
+/// are kept from every entry.
+pub fn merge_chinese_word_data(
+    status_data: &mut serde_json::Value,
+    definition_data: &mut serde_json::Value,
+    context_data: &mut serde_json::Value,
+) {
+    let mut statuses: HashMap<String, (String, serde_json::Value)> = HashMap::new();
+    let mut definitions: HashMap<String, serde_json::Value> = HashMap::new();
+    let mut contexts: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+
+    // the shared entry comes last so that it wins over script specific ones
+    for lang in &["zh-CN", "zh-TW", SHARED_CHINESE_LANG] {
+        if let Some(serde_json::Value::Object(status_map)) = status_data.get(lang) {
+            for (status, words) in status_map {
+                if let serde_json::Value::Object(words) = words {
+                    for (word, level) in words {
+                        statuses.insert(
+                            chinese::to_simplified(word),
+                            (status.clone(), level.clone()),
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(serde_json::Value::Object(definition_map)) = definition_data.get(lang) {
+            for (word, definition) in definition_map {
+                definitions.insert(chinese::to_simplified(word), definition.clone());
+            }
+        }
+
+        if let Some(serde_json::Value::Object(context_map)) = context_data.get(lang) {
+            for (word, word_contexts) in context_map {
+                if let serde_json::Value::Array(word_contexts) = word_contexts {
+                    contexts
+                        .entry(chinese::to_simplified(word))
+                        .or_default()
+                        .extend(word_contexts.iter().cloned());
+                }
+            }
+        }
+    }
+
+    let mut merged_statuses = json!({ "learning": {}, "known": {}, "ignored": {} });
+    for (word, (status, level)) in statuses {
+        for variant in get_script_variants(&word) {
+            merged_statuses[&status][variant] = level.clone();
+        }
+    }
+
+    let mut merged_definitions = json!({});
+    for (word, definition) in definitions {
+        for variant in get_script_variants(&word) {
+            merged_definitions[variant] = definition.clone();
+        }
+    }
+
+    let mut merged_contexts = json!({});
+    for (word, word_contexts) in contexts {
+        for variant in get_script_variants(&word) {
+            merged_contexts[variant] = json!(word_contexts);
+        }
+    }
+
+    for lang in CHINESE_LANGS {
+        status_data[*lang] = merged_statuses.clone();
+        definition_data[*lang] = merged_definitions.clone();
+        context_data[*lang] = merged_contexts.clone();
+    }
+}
+
+/// Returns the unique words of an article that count towards its vocabulary,
+/// leaving out punctuation and whitespace.
+pub fn get_vocabulary(unique_words: &serde_json::Value) -> Vec<String> {
+    match unique_words {
+        serde_json::Value::Object(unique_words) => unique_words
+            .keys()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .cloned()
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the words of a Chinese article in simplified characters, which a
+/// vocabulary shared between both scripts is matched by.
+pub fn get_simplified_words(words: &[String], lang: &str) -> Option<Vec<String>> {
+    if !is_chinese(lang) {
+        return None;
+    }
+
+    Some(words.iter().map(|word| to_simplified(word)).collect())
+}
+
+/// Returns the tone marked pinyin of every word of a Chinese text, with `None`
+/// for punctuation and the other words without Chinese characters.
+pub fn get_pinyin(words: &[String], lang: &str) -> Option<Vec<Option<String>>> {
+    if !is_chinese(lang) {
+        return None;
+    }
+
+    Some(
+        words
+            .iter()
+            .map(|word| pinyin::get_pinyin(word))
+            .collect::<Vec<Option<String>>>(),
+    )
+}
+
+/// The language a text was detected to be in, along with the other languages
+/// it could be in, most likely first.
+pub struct LanguageDetection {
+    pub lang: &'static str,
+    // from 0 to 1, the share of the text the language accounts for
+    pub confidence: f32,
+    pub candidates: Vec<(&'static str, f32)>,
+}
+
+/// The confidence under which a detected language isn't used for an article.
+pub const MIN_DETECTION_CONFIDENCE: f32 = 0.6;
+
+/// Guesses the language of a text from its scripts and, for languages written
+/// in Latin script, its function words and accented letters. Returns None for
+/// a text without letters.
+pub fn detect_language(text: &str) -> Option<LanguageDetection> {
+    let candidates = detect::get_language_scores(text);
+    let (lang, confidence) = *candidates.first()?;
+
+    Some(LanguageDetection {
+        lang,
+        confidence: (confidence * 100.0).round() / 100.0,
+        candidates,
+    })
+}
+
+/// Scores how hard the vocabulary of a text is, from 1 to 6, as the mean
+/// level of its distinct words. Levels come from the HSK and CEFR lists, and
+/// from word frequency for words they don't cover. Returns None for the
+/// languages without a list.
+pub fn get_difficulty(
+    words: &[String],
+    base_forms: &[Option<String>],
+    token_types: &[String],
+    lang: &str,
+) -> Option<f32> {
+    let difficulty_list = if is_chinese(lang) {
+        &*difficulty::CHINESE
+    } else if lang == "en" {
+        &*difficulty::ENGLISH
+    } else {
+        return None;
+    };
+
+    let counted_words = words
+        .iter()
+        .zip(base_forms)
+        .zip(token_types)
+        .filter(|(_, token_type)| *token_type == TokenType::Word.as_str())
+        .map(|((word, base_form), _)| base_form.as_ref().unwrap_or(word).as_str());
+
+    difficulty_list.get_difficulty(counted_words)
+}
+
+/// Splits the text into words along with the lemma of each word, which is
+/// `None` when the word is its own lemma.
+pub fn get_words_and_base_forms(
+    text: &str,
+    tokenizer: &dyn Tokenizer,
+    lemmatizer: Option<&dyn Lemmatizer>,
+) -> (Vec<String>, Vec<Option<String>>) {
+    tokenizer
+        .get_tokens(text)
+        .into_iter()
+        .map(|token| {
+            let base_form = match (token.base_form, lemmatizer) {
+                (None, Some(lemmatizer)) => lemmatizer.get_lemma(token.text),
+                (base_form, _) => base_form,
+            };
+            (token.text.to_string(), base_form)
+        })
+        .unzip()
+}
+
+/// Returns the byte offset of every word in the text. Words are found in
+/// order, so a word the tokenizer dropped from the text only shifts the search.
+pub fn get_word_offsets(text: &str, words: &[String]) -> Vec<usize> {
+    let mut offsets: Vec<usize> = Vec::with_capacity(words.len());
+    let mut cursor = 0;
+
+    for word in words {
+        let offset = match text[cursor..].find(&word[..]) {
+            Some(index) => cursor + index,
+            None => cursor,
+        };
+
+        offsets.push(offset);
+        cursor = (offset + word.len()).min(text.len());
+        while !text.is_char_boundary(cursor) {
+            cursor += 1;
+        }
+    }
+
+    offsets
+}
+
+/// What kind of text a token is. Only words are vocabulary to be learned,
+/// the other tokens are left out of an article's word counts.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TokenType {
+    Word,
+    Punctuation,
+    Whitespace,
+    Number,
+    Url,
+    Email,
+    // a word in another script than the language's own
+    Foreign,
+    ProperNoun,
+}
+
+impl TokenType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenType::Word => "word",
+            TokenType::Punctuation => "punctuation",
+            TokenType::Whitespace => "whitespace",
+            TokenType::Number => "number",
+            TokenType::Url => "url",
+            TokenType::Email => "email",
+            TokenType::Foreign => "foreign",
+            TokenType::ProperNoun => "proper_noun",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockType {
+    Paragraph,
+    Heading,
+    Quote,
+    ListItem,
+}
+
+impl BlockType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BlockType::Paragraph => "paragraph",
+            BlockType::Heading => "heading",
+            BlockType::Quote => "quote",
+            BlockType::ListItem => "list_item",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkType {
+    Emphasis,
+    Strong,
+    Strikethrough,
+    Code,
+    Link,
+}
+
+/// Inline formatting over part of a text.
+#[derive(Serialize, Deserialize)]
+pub struct Mark {
+    #[serde(rename = "type")]
+    pub mark_type: MarkType,
+    // an exclusive range of bytes of the text, or of words once aligned
+    pub start: usize,
+    pub end: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// A phrase found in a text, which is learned as a single entry under the
+/// key it's stored by.
+#[derive(Serialize, Deserialize)]
+pub struct PhraseSpan {
+    pub phrase: String,
+    // an exclusive range of words
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Returns the key a phrase, like a phrasal verb or a chengyu, is stored under
+/// as a vocabulary entry, or None if it has no words in the language.
+pub fn get_phrase_key(phrase: &str, lang: &str) -> Option<String> {
+    phrases::get_phrase_key(phrase, lang)
+}
+
+/// The language a user's phrases are kept under. Chinese phrases are keyed in
+/// simplified characters, so one list serves both scripts.
+pub fn get_phrase_lang(lang: &str) -> &str {
+    if is_chinese(lang) {
+        SHARED_CHINESE_LANG
+    } else {
+        lang
+    }
+}
+
+/// Finds the phrases of the language's bundled list and of the user's own
+/// phrases, given by their keys, in the words of an article.
+pub fn get_phrase_spans(
+    words: &[String],
+    base_forms: &[Option<String>],
+    token_types: &[String],
+    lang: &str,
+    user_phrases: &[String],
+) -> Vec<PhraseSpan> {
+    phrases::get_phrase_spans(words, base_forms, token_types, lang, user_phrases)
+}
+
+/// The text of an article's content to be split into words, along with the
+/// structure its markup gave it.
+pub struct ContentText {
+    pub text: String,
+    // the byte offset and type of every block, or None for plain text, whose
+    // blocks are guessed from its lines instead
+    pub blocks: Option<Vec<(usize, BlockType)>>,
+    pub marks: Vec<Mark>,
+}
+
+/// Takes the text out of content in the given format, either plain or
+/// markdown.
+pub fn get_content_text(content: &str, content_format: &str) -> Result<ContentText, &'static str> {
+    match content_format {
+        "plain" => Ok(ContentText {
+            text: content.to_string(),
+            blocks: None,
+            marks: vec![],
+        }),
+        "markdown" => Ok(markdown::parse_markdown(content)),
+        _ => Err("Unsupported content format"),
+    }
+}
+
+/// Where every token of a text is and what kind of token it is, each indexed
+/// the same as the words, along with the block type of every paragraph.
+pub struct TokenData {
+    // offsets in unicode scalar values
+    pub char_offsets: Vec<i32>,
+    pub byte_offsets: Vec<i32>,
+    pub token_types: Vec<String>,
+    pub paragraph_indices: Vec<i32>,
+    pub block_types: Vec<String>,
+}
+
+const URL_PREFIXES: &[&str] = &["https://", "http://", "www."];
+
+/// Returns the byte ranges of the links in the text. A link runs until
+/// whitespace or a quote, without the punctuation ending a sentence after it.
+fn get_url_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = vec![];
+    let mut prev: Option<char> = None;
+
+    for (start, c) in text.char_indices() {
+        let after_word = matches!(prev, Some(prev) if prev.is_alphanumeric());
+        let in_url = matches!(spans.last(), Some((_, end)) if start < *end);
+        prev = Some(c);
+
+        if after_word
+            || in_url
+            || !URL_PREFIXES
+                .iter()
+                .any(|prefix| text[start..].starts_with(prefix))
+        {
+            continue;
+        }
+
+        let end = text[start..]
+            .find(|c: char| c.is_whitespace() || "<>\"“”‘’「」『』《》（）。，、！？".contains(c))
+            .map(|length| start + length)
+            .unwrap_or_else(|| text.len());
+        let url = text[start..end].trim_end_matches(|c: char| ".,;:!?)]}'\"".contains(c));
+
+        spans.push((start, start + url.len()));
+    }
+
+    spans
+}
+
+/// Returns the byte ranges of the email addresses in the text.
+fn get_email_spans(text: &str) -> Vec<(usize, usize)> {
+    let is_local_char = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain_char = |c: char| c.is_ascii_alphanumeric() || ".-".contains(c);
+
+    text.match_indices('@')
+        .filter_map(|(at, _)| {
+            let start = text[..at]
+                .rfind(|c: char| !is_local_char(c))
+                .map(|index| index + text[index..].chars().next().unwrap().len_utf8())
+                .unwrap_or(0);
+            let domain_end = text[at + 1..]
+                .find(|c: char| !is_domain_char(c))
+                .map(|length| at + 1 + length)
+                .unwrap_or_else(|| text.len());
+            let domain = text[at + 1..domain_end].trim_end_matches(|c| ".-".contains(c));
+
+            if start < at && domain.contains('.') && !domain.starts_with('.') {
+                Some((start, at + 1 + domain.len()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn is_number(word: &str) -> bool {
+    word.chars().any(char::is_numeric)
+        && word.chars().all(|c| c.is_numeric() || c == '.' || c == ',')
+}
+
+const LIST_MARKERS: &[&str] = &["- ", "* ", "+ ", "• ", "・"];
+// the longest line without closing punctuation taken as a heading
+const MAX_HEADING_LENGTH: usize = 60;
+
+/// Guesses the kind of block a line of text is from how it starts, or from it
+/// being short and missing the punctuation that ends a sentence. The last line
+/// has nothing to head, so it's only a heading when marked as one.
+fn get_block_type(line: &str, is_last: bool) -> BlockType {
+    let line = line.trim();
+    let ordinal_length = line.find(|c: char| !c.is_ascii_digit()).unwrap_or(0);
+
+    if line.starts_with('#') {
+        BlockType::Heading
+    } else if line.starts_with('>') {
+        BlockType::Quote
+    } else if LIST_MARKERS.iter().any(|marker| line.starts_with(marker))
+        || (ordinal_length > 0
+            && (line[ordinal_length..].starts_with(". ")
+                || line[ordinal_length..].starts_with(") ")))
+    {
+        BlockType::ListItem
+    } else if !is_last
+        && line.chars().count() <= MAX_HEADING_LENGTH
+        && !line.ends_with(|c: char| ".,;:!?…\"'”’」』)。，；：！？".contains(c))
+    {
+        BlockType::Heading
+    } else {
+        BlockType::Paragraph
+    }
+}
+
+// punctuation after which a capitalized word may just be starting a sentence
+const SENTENCE_END_CHARS: &str = ".!?:…。！？";
+
+/// Finds the offsets, type and paragraph of every token. Paragraphs are
+/// separated by line breaks, and whitespace belongs to the paragraph before it.
+pub fn get_token_data(text: &str, words: &[String], tokenizer: &dyn Tokenizer) -> TokenData {
+    let url_spans = get_url_spans(text);
+    let email_spans = get_email_spans(text);
+    let in_spans = |spans: &[(usize, usize)], offset: usize| {
+        spans
+            .iter()
+            .any(|(start, end)| (*start..*end).contains(&offset))
+    };
+    let byte_offsets = get_word_offsets(text, words);
+
+    let mut token_data = TokenData {
+        char_offsets: Vec::with_capacity(words.len()),
+        byte_offsets: Vec::with_capacity(words.len()),
+        token_types: Vec::with_capacity(words.len()),
+        paragraph_indices: Vec::with_capacity(words.len()),
+        block_types: vec![],
+    };
+
+    let mut char_offset = 0;
+    let mut last_byte_offset = 0;
+    let mut paragraph_index = 0;
+    let mut paragraph_has_text = false;
+    let mut paragraph_ended = false;
+    let mut paragraph_starts: Vec<usize> = vec![];
+    // capitalized words that can't be capitalized for starting a sentence
+    let mut at_sentence_start = true;
+    let mut name_indices: Vec<usize> = vec![];
+
+    for (index, (word, byte_offset)) in words.iter().zip(byte_offsets).enumerate() {
+        char_offset += text[last_byte_offset..byte_offset].chars().count();
+        last_byte_offset = byte_offset;
+
+        let token_type = if word.chars().all(char::is_whitespace) {
+            TokenType::Whitespace
+        } else if in_spans(&url_spans, byte_offset) {
+            TokenType::Url
+        } else if in_spans(&email_spans, byte_offset) {
+            TokenType::Email
+        } else if is_number(word) {
+            TokenType::Number
+        } else if tokenizer.is_punctuation(word) || word.chars().all(|c| !c.is_alphanumeric()) {
+            TokenType::Punctuation
+        } else if tokenizer.is_foreign_word(word) {
+            TokenType::Foreign
+        } else {
+            TokenType::Word
+        };
+
+        if token_type == TokenType::Whitespace {
+            paragraph_ended |= paragraph_has_text && word.contains('\n');
+        } else {
+            if paragraph_ended {
+                paragraph_index += 1;
+                paragraph_ended = false;
+                at_sentence_start = true;
+            }
+            if token_type == TokenType::Word && !at_sentence_start && tokenizer.is_capitalized(word)
+            {
+                name_indices.push(index);
+            }
+            if token_type == TokenType::Punctuation {
+                at_sentence_start |= word.contains(|c| SENTENCE_END_CHARS.contains(c));
+            } else {
+                at_sentence_start = false;
+            }
+            if paragraph_starts.len() <= paragraph_index as usize {
+                paragraph_starts.push(byte_offset);
+            }
+            paragraph_has_text = true;
+        }
+
+        token_data
+            .char_offsets
+            .push(char_offset.try_into().unwrap());
+        token_data
+            .byte_offsets
+            .push(byte_offset.try_into().unwrap());
+        token_data.token_types.push(token_type.as_str().to_string());
+        token_data.paragraph_indices.push(paragraph_index);
+    }
+
+    token_data.block_types = paragraph_starts
+        .iter()
+        .enumerate()
+        .map(|(index, start)| {
+            let line = text[*start..].split('\n').next().unwrap_or("");
+            let is_last = index + 1 == paragraph_starts.len();
+            get_block_type(line, is_last).as_str().to_string()
+        })
+        .collect();
+
+    mark_proper_nouns(words, &mut token_data, &name_indices[..], tokenizer);
+
+    token_data
 }
 
-fn get_words_chinese_slice(text: &str) -> Vec<&str> {
-    JIEBA.cut(text, false)
+/// Marks the words capitalized in the middle of a sentence as proper nouns,
+/// along with their other capitalized uses, as long as the text never writes
+/// them in lowercase. Headings are left out, as they're often in title case.
+fn mark_proper_nouns(
+    words: &[String],
+    token_data: &mut TokenData,
+    name_indices: &[usize],
+    tokenizer: &dyn Tokenizer,
+) {
+    let is_in_heading = |index: usize| {
+        let paragraph = token_data.paragraph_indices[index] as usize;
+        token_data.block_types.get(paragraph).map(String::as_str)
+            == Some(BlockType::Heading.as_str())
+    };
+
+    let names: HashSet<String> = name_indices
+        .iter()
+        .filter(|index| !is_in_heading(**index))
+        .map(|index| words[*index].to_lowercase())
+        .collect();
+    let lowercase_words: HashSet<&str> = words
+        .iter()
+        .zip(&token_data.token_types)
+        .filter(|(word, token_type)| {
+            *token_type == TokenType::Word.as_str() && !tokenizer.is_capitalized(word)
+        })
+        .map(|(word, _)| word.as_str())
+        .collect();
+
+    for (word, token_type) in words.iter().zip(token_data.token_types.iter_mut()) {
+        if *token_type != TokenType::Word.as_str() || !tokenizer.is_capitalized(word) {
+            continue;
+        }
+
+        let lowercase = word.to_lowercase();
+        if names.contains(&lowercase) && !lowercase_words.contains(&lowercase[..]) {
+            *token_type = TokenType::ProperNoun.as_str().to_string();
+        }
+    }
 }
 
-fn get_words_chinese_owned(text: &str) -> Vec<String> {
-    JIEBA
-        .cut(text, false)
+/// Gives the paragraphs the block types of the markup of the text, and
+/// returns its marks as ranges of words.
+pub fn apply_markup(content_text: &ContentText, token_data: &mut TokenData) -> Vec<Mark> {
+    let byte_offsets = &token_data.byte_offsets;
+
+    if let Some(ref blocks) = content_text.blocks {
+        let mut block_types: Vec<String> = vec![];
+        for (index, paragraph) in token_data.paragraph_indices.iter().enumerate() {
+            if block_types.len() > *paragraph as usize
+                || token_data.token_types[index] == TokenType::Whitespace.as_str()
+            {
+                continue;
+            }
+
+            let offset = byte_offsets[index] as usize;
+            let block_type = blocks
+                .iter()
+                .rev()
+                .find(|(start, _)| *start <= offset)
+                .map_or(BlockType::Paragraph, |(_, block_type)| *block_type);
+            block_types.push(block_type.as_str().to_string());
+        }
+
+        token_data.block_types = block_types;
+    }
+
+    let word_at = |byte_offset: usize| {
+        byte_offsets
+            .iter()
+            .position(|offset| *offset as usize >= byte_offset)
+            .unwrap_or(byte_offsets.len())
+    };
+
+    content_text
+        .marks
         .iter()
-        .map(|slice| slice.to_string())
-        .collect::<Vec<String>>()
+        .map(|mark| Mark {
+            mark_type: mark.mark_type,
+            start: word_at(mark.start),
+            end: word_at(mark.end),
+            url: mark.url.clone(),
+        })
+        .filter(|mark| mark.start < mark.end)
+        .collect()
 }
 
 pub fn get_sentences<'a>(
     text: &'a str,
     words: &'a [String],
+    paragraph_indices: &[i32],
+    tokenizer: &dyn Tokenizer,
 ) -> Option<(Vec<Vec<&'a str>>, Vec<i32>)> {
-    let mut sentence_stops: Vec<i32> = vec![0];
-
-    let sentence_strs = text.split_sentence_bounds().collect::<Vec<&str>>();
-
-    // Gets the indicies inside the words array of the ends of sentences
-    // in the entire text. That is, the 0-indexed ith position of sentence_stops
-    // will contain the index inside the words array that corresponds to
-    // the exclusive end of the 1-indexed ith sentence. So sentence_stops[1]
-    // points to the exclusive end of the first sentence inside the words array,
-    // and we assume that sentence_stops[0], which is always 0, is the inclusive start
-    // of the first sentence.
-    let mut words_index = 0;
-    for sentence in &sentence_strs {
-        let mut length = sentence.len() as i32;
-        while length > 0 {
-            length -= words[words_index].len() as i32;
-            words_index += 1;
-        }
-        sentence_stops.push(words_index.try_into().unwrap());
+    // the exclusive byte offset each sentence ends at
+    let mut sentence_ends: Vec<usize> = vec![];
+    let mut sentence_end = 0;
+    for sentence in tokenizer.get_sentences(text) {
+        sentence_end += sentence.len();
+        sentence_ends.push(sentence_end);
     }
 
+    // sentence_stops[i] is the index in the words array of the exclusive end
+    // of the ith sentence, counting from 1, with sentence_stops[0] always 0.
+    // Every word belongs to the sentence its first byte is in, so a word the
+    // sentence splitter cut through is kept whole, and a sentence left without
+    // words is dropped. A sentence never runs over into the next paragraph.
+    let mut sentence_stops: Vec<i32> = vec![0];
     let mut sentence_arr: Vec<Vec<&str>> = vec![];
-    let sentence_count = sentence_strs.len();
+    let mut sentence: Vec<&str> = vec![];
+    let mut sentence_index = 0;
+    let mut sentence_paragraph = 0;
 
-    for sentence_index in 1..(sentence_count + 1) {
-        // the start index of sentence i (sentence_index) in the words array (inclusive)
-        let start_index = sentence_stops[sentence_index - 1] as usize; // indicies are certainly positive, no problem converting
-                                                                       // the end index of sentence i (sentence_index) in the words array (exclusive)
-        let end_index = sentence_stops[sentence_index] as usize;
-
-        let mut sentence: Vec<&str> = vec![];
+    for (index, (word, offset)) in words.iter().zip(get_word_offsets(text, words)).enumerate() {
+        let paragraph = paragraph_indices.get(index).copied().unwrap_or(0);
+        let mut sentence_ended = paragraph != sentence_paragraph;
+        while sentence_index < sentence_ends.len() && offset >= sentence_ends[sentence_index] {
+            sentence_index += 1;
+            sentence_ended = true;
+        }
 
-        for word in words.iter().take(end_index).skip(start_index) {
-            sentence.push(&word[..]);
+        if sentence_ended && !sentence.is_empty() {
+            sentence_arr.push(sentence);
+            sentence = vec![];
+            sentence_stops.push(words_before(&sentence_arr));
         }
 
+        sentence_paragraph = paragraph;
+        sentence.push(&word[..]);
+    }
+
+    if !sentence.is_empty() {
         sentence_arr.push(sentence);
+        sentence_stops.push(words_before(&sentence_arr));
     }
 
     Some((sentence_arr, sentence_stops))
 }
 
+/// Returns the sentence of an article the word at an index is in, without the
+/// whitespace around it, from the sentences stored by `get_sentences`.
+pub fn get_word_sentence(
+    sentences: &serde_json::Value,
+    sentence_stops: &[i32],
+    word_index: usize,
+) -> Option<String> {
+    // the first stop past the word is the end of its sentence
+    let sentence_index = sentence_stops
+        .iter()
+        .position(|stop| *stop as usize > word_index)?
+        .checked_sub(1)?;
+    let sentence = sentences
+        .get(sentence_index)?
+        .as_array()?
+        .iter()
+        .filter_map(|word| word.as_str())
+        .collect::<String>();
+
+    match sentence.trim() {
+        "" => None,
+        sentence => Some(sentence.to_string()),
+    }
+}
+
+fn words_before(sentences: &[Vec<&str>]) -> i32 {
+    sentences
+        .iter()
+        .map(|sentence| sentence.len())
+        .sum::<usize>()
+        .try_into()
+        .unwrap()
+}
+
 lazy_static! {
     static ref STOP_CHARS: HashSet<&'static str> =
         "!\"#$%&'()*+,-./:;<=>?@[\\]^_{|}~`。？！，、；：“ ” ‘ ’「」『』（）【】—…～	
@@ -101,8 +923,13 @@ lazy_static! {
             .collect();
 }
 
+/// Counts the words of an article by their dictionary form and indexes where
+/// they are. Tokens that aren't words to learn, like punctuation, numbers and
+/// names, are indexed as stop words instead.
 pub fn get_article_main_data(
     words: &[String],
+    base_forms: &[Option<String>],
+    token_types: &[String],
 ) -> (
     serde_json::Value,
     usize,
@@ -130,8 +957,13 @@ pub fn get_article_main_data(
     };
 
     for (index, word) in words.iter().enumerate() {
-        let lowercase = word.to_lowercase();
-        if !STOP_CHARS.contains(&lowercase[..]) {
+        if token_types[index] == TokenType::Word.as_str() {
+            // inflected words are counted under their dictionary form
+            let lowercase = match base_forms[index] {
+                Some(ref base_form) => base_form.to_lowercase(),
+                None => word.to_lowercase(),
+            };
+
             match unique_words_map.get_mut(&lowercase) {
                 Some(num_val) => {
                     let new_num = num_val.as_i64().unwrap() + 1i64;
@@ -153,11 +985,7 @@ pub fn get_article_main_data(
                 }
             }
         } else {
-            match stop_word_map.get_mut(&lowercase) {
-                Some(_) | None => {
-                    stop_word_map.insert(index.to_string(), json!(true));
-                }
-            }
+            stop_word_map.insert(index.to_string(), json!(true));
         }
     }
 
@@ -167,121 +995,357 @@ pub fn get_article_main_data(
 pub fn get_or_query_string(
     string_opt: &Option<String>,
     lang_opt: &Option<String>,
-) -> Option<String> {
+) -> Result<Option<String>, &'static str> {
     if string_opt.is_some() && lang_opt.is_some() {
-        Some(
-            get_words_slice(
-                &string_opt.as_ref().unwrap()[..],
-                &lang_opt.as_ref().unwrap()[..],
-            )
-            .iter()
-            .filter_map(|&word| match word {
-                " " => None,
-                _ => Some(word),
-            })
-            .collect::<Vec<&str>>()
-            .join(" OR "),
-        )
+        let tokenizer = get_tokenizer(&lang_opt.as_ref().unwrap()[..])?;
+
+        Ok(Some(
+            tokenizer
+                .get_words(&string_opt.as_ref().unwrap()[..])
+                .iter()
+                .filter_map(|&word| match word {
+                    " " => None,
+                    _ => Some(word),
+                })
+                .collect::<Vec<&str>>()
+                .join(" OR "),
+        ))
     } else {
-        None
+        Ok(None)
     }
 }
 
-const SMALL_PAGE_SIZE: i32 = 100;
-const MEDIUM_PAGE_SIZE: i32 = 150;
-const LARGE_PAGE_SIZE: i32 = 200;
-
-pub fn get_pages(sentences_opt: &Option<(Vec<Vec<&str>>, Vec<i32>)>) -> serde_json::Value {
-    let mut pages_sm: Vec<Vec<&str>> = vec![];
-    let mut pages_md: Vec<Vec<&str>> = vec![];
-    let mut pages_lg: Vec<Vec<&str>> = vec![];
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageUnit {
+    Words,
+    Chars,
+}
 
-    if sentences_opt.is_none() {
-        return json!([
-            {
-                "pages": pages_sm,
-                "wordIndexMaps": []
-            },
-            {
-                "pages": pages_md,
-                "wordIndexMaps": []
-            },
-            {
-                "pages": pages_lg,
-                "wordIndexMaps": []
-            }
-        ]);
+impl PageUnit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PageUnit::Words => "words",
+            PageUnit::Chars => "chars",
+        }
     }
 
-    let sentences = &sentences_opt.as_ref().unwrap().0;
-
-    let mut remain_pg_len_sm: i32 = SMALL_PAGE_SIZE;
-    let mut curr_pg_sm = 0;
-    pages_sm.push(vec![]);
+    pub fn parse(unit: &str) -> Option<PageUnit> {
+        match unit {
+            "words" => Some(PageUnit::Words),
+            "chars" => Some(PageUnit::Chars),
+            _ => None,
+        }
+    }
+}
 
-    let mut remain_pg_len_md: i32 = MEDIUM_PAGE_SIZE;
-    let mut curr_pg_md = 0;
-    pages_md.push(vec![]);
+pub const DEFAULT_PAGE_SIZE: i32 = 150;
+pub const MIN_PAGE_SIZE: i32 = 10;
+pub const MAX_PAGE_SIZE: i32 = 10000;
 
-    let mut remain_pg_len_lg: i32 = LARGE_PAGE_SIZE;
-    let mut curr_pg_lg = 0;
-    pages_lg.push(vec![]);
+/// How long pages should be, in words or in characters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageSize {
+    pub size: i32,
+    pub unit: PageUnit,
+}
 
-    for sentence in sentences {
-        for word in sentence {
-            pages_sm[curr_pg_sm].push(word);
-            pages_md[curr_pg_md].push(word);
-            pages_lg[curr_pg_lg].push(word);
+impl PageSize {
+    /// Returns None for an unknown unit or a size out of the allowed range.
+    pub fn new(size: i32, unit: &str) -> Option<PageSize> {
+        if (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&size) {
+            Some(PageSize {
+                size,
+                unit: PageUnit::parse(unit)?,
+            })
+        } else {
+            None
         }
+    }
+}
 
-        let length: i32 = sentence.len().try_into().unwrap();
-        remain_pg_len_sm -= length;
-        remain_pg_len_md -= length;
-        remain_pg_len_lg -= length;
+// How full a page has to be, in percent of its size, to end at the end of a
+// paragraph, and how far over it may run to reach one instead of ending in the
+// middle of a paragraph.
+const MIN_PARAGRAPH_BREAK_FILL: i32 = 75;
+const MAX_PAGE_FILL: i32 = 125;
 
-        if remain_pg_len_sm <= 0 {
-            remain_pg_len_sm = SMALL_PAGE_SIZE;
-            curr_pg_sm += 1;
-            pages_sm.push(vec![]);
-        }
+/// Returns the index in the words array of the first word of every page.
+/// Pages end at the end of a sentence, preferably at the end of a paragraph
+/// where one is close, and never right after a heading. Only the sentence
+/// stops and paragraphs decide the pages, so they're the same every time.
+pub fn get_page_starts(
+    words: &[String],
+    token_types: &[String],
+    sentence_stops: &[i32],
+    paragraph_indices: &[i32],
+    block_types: &[String],
+    page_size: PageSize,
+) -> Vec<i32> {
+    if words.is_empty() {
+        return vec![];
+    }
+
+    // an article without sentences is taken as one long sentence
+    let whole_text: [i32; 2] = [0, words.len().try_into().unwrap()];
+    let sentence_stops = if sentence_stops.len() > 1 {
+        sentence_stops
+    } else {
+        &whole_text[..]
+    };
+    let sentence_count = sentence_stops.len() - 1;
+
+    // words without their token types, as in articles which haven't been
+    // segmented again since they were stored, are measured in characters
+    let get_length = |start: i32, end: i32| -> i32 {
+        let range = start as usize..end as usize;
+        let length = match (page_size.unit, token_types.get(range.clone())) {
+            (PageUnit::Words, Some(token_types)) => token_types
+                .iter()
+                .filter(|token_type| {
+                    *token_type != TokenType::Whitespace.as_str()
+                        && *token_type != TokenType::Punctuation.as_str()
+                })
+                .count(),
+            _ => words
+                .get(range)
+                .unwrap_or_default()
+                .iter()
+                .map(|word| word.chars().count())
+                .sum(),
+        };
+
+        length.try_into().unwrap()
+    };
+
+    let sentence_lengths = sentence_stops
+        .windows(2)
+        .map(|stops| get_length(stops[0], stops[1]))
+        .collect::<Vec<i32>>();
+    let sentence_paragraphs = sentence_stops[..sentence_count]
+        .iter()
+        .map(|stop| paragraph_indices.get(*stop as usize).copied().unwrap_or(0))
+        .collect::<Vec<i32>>();
 
-        if remain_pg_len_md <= 0 {
-            remain_pg_len_md = MEDIUM_PAGE_SIZE;
-            curr_pg_md += 1;
-            pages_md.push(vec![]);
+    // the length left in the paragraph after each sentence
+    let mut paragraph_remains: Vec<i32> = vec![0; sentence_count];
+    for index in (0..sentence_count.saturating_sub(1)).rev() {
+        if sentence_paragraphs[index] == sentence_paragraphs[index + 1] {
+            paragraph_remains[index] = paragraph_remains[index + 1] + sentence_lengths[index + 1];
         }
+    }
+
+    let mut page_starts: Vec<i32> = vec![0];
+    let mut page_length: i32 = 0;
+
+    for index in 0..sentence_count {
+        let is_heading = block_types
+            .get(sentence_paragraphs[index] as usize)
+            .map(|block_type| &block_type[..])
+            == Some(BlockType::Heading.as_str());
+        page_length += sentence_lengths[index];
 
-        if remain_pg_len_lg <= 0 {
-            remain_pg_len_lg = LARGE_PAGE_SIZE;
-            curr_pg_lg += 1;
-            pages_lg.push(vec![]);
+        let fill = page_length * 100 / page_size.size;
+        let is_page_end = if paragraph_remains[index] == 0 {
+            if is_heading {
+                fill >= MAX_PAGE_FILL
+            } else {
+                fill >= MIN_PARAGRAPH_BREAK_FILL
+            }
+        } else {
+            fill >= 100
+                && (page_length + paragraph_remains[index]) * 100 / page_size.size > MAX_PAGE_FILL
+        };
+
+        if is_page_end && index + 1 < sentence_count {
+            page_starts.push(sentence_stops[index + 1]);
+            page_length = 0;
         }
     }
 
-    // remove potential empty last pages
-    if pages_sm[pages_sm.len() - 1].is_empty() {
-        pages_sm.pop();
+    page_starts
+}
+
+/// Returns the page the word at the index is on, so that a place in an
+/// article can be found again after the page size changes.
+pub fn get_page_index(page_starts: &[i32], word_index: i32) -> usize {
+    match page_starts.binary_search(&word_index) {
+        Ok(page_index) => page_index,
+        Err(page_index) => page_index.saturating_sub(1),
     }
+}
 
-    if pages_md[pages_md.len() - 1].is_empty() {
-        pages_md.pop();
+/// A page of an article, with its blocks and marks as ranges of the words of
+/// the page.
+pub struct Page {
+    // the exclusive range of the words of the article on the page
+    pub start: usize,
+    pub end: usize,
+    pub blocks: Vec<serde_json::Value>,
+    pub marks: Vec<Mark>,
+}
+
+/// Returns the page at the index, or None past the last page.
+pub fn get_page(
+    word_count: usize,
+    paragraph_indices: &[i32],
+    block_types: &[String],
+    marks: &[Mark],
+    page_starts: &[i32],
+    page_index: usize,
+) -> Option<Page> {
+    let start = *page_starts.get(page_index)? as usize;
+    let end = page_starts
+        .get(page_index + 1)
+        .map_or(word_count, |end| *end as usize);
+
+    Some(Page {
+        start,
+        end,
+        blocks: get_page_blocks(paragraph_indices, block_types, start, end),
+        marks: get_page_marks(marks, start, end),
+    })
+}
+
+/// Keeps the entries of the word index and stop word maps which are about the
+/// words from start to end. Word indices stay indices into the whole article.
+pub fn get_page_word_maps(
+    word_index_map: &serde_json::Value,
+    stop_word_map: &serde_json::Value,
+    start: usize,
+    end: usize,
+) -> (serde_json::Value, serde_json::Value) {
+    let is_on_page = |index: usize| (start..end).contains(&index);
+    let is_value_on_page = |index: &serde_json::Value| matches!(index.as_u64(), Some(index) if is_on_page(index as usize));
+
+    let mut page_word_index_map = serde_json::Map::new();
+    if let serde_json::Value::Object(map) = word_index_map {
+        for (word, indices) in map {
+            let page_indices = indices.as_array().map_or(vec![], |indices| {
+                indices
+                    .iter()
+                    .filter(|index| is_value_on_page(index))
+                    .cloned()
+                    .collect()
+            });
+
+            if !page_indices.is_empty() {
+                page_word_index_map.insert(word.clone(), json!(page_indices));
+            }
+        }
     }
 
-    if pages_lg[pages_lg.len() - 1].is_empty() {
-        pages_lg.pop();
+    let mut page_stop_word_map = serde_json::Map::new();
+    if let serde_json::Value::Object(map) = stop_word_map {
+        for (index, value) in map {
+            if matches!(index.parse::<usize>(), Ok(index) if is_on_page(index)) {
+                page_stop_word_map.insert(index.clone(), value.clone());
+            }
+        }
     }
 
-    json!([
-        {
-            "pages": pages_sm,
-        },
-        {
-            "pages": pages_md,
-        },
-        {
-            "pages": pages_lg,
+    (
+        serde_json::Value::Object(page_word_index_map),
+        serde_json::Value::Object(page_stop_word_map),
+    )
+}
+
+/// Returns the blocks on a page, as ranges of the words of the page along with
+/// the index and type of the paragraph each is part of.
+fn get_page_blocks(
+    paragraph_indices: &[i32],
+    block_types: &[String],
+    start: usize,
+    end: usize,
+) -> Vec<serde_json::Value> {
+    let mut blocks: Vec<serde_json::Value> = vec![];
+
+    for index in start..end {
+        let paragraph = paragraph_indices.get(index).copied().unwrap_or(0);
+        match blocks.last_mut() {
+            Some(block) if block["paragraph"] == json!(paragraph) => {
+                block["end"] = json!(index + 1 - start);
+            }
+            _ => {
+                let block_type = block_types
+                    .get(paragraph as usize)
+                    .map_or(BlockType::Paragraph.as_str(), |block_type| &block_type[..]);
+                blocks.push(json!({
+                    "type": block_type,
+                    "paragraph": paragraph,
+                    "start": index - start,
+                    "end": index + 1 - start,
+                }));
+            }
         }
-    ])
+    }
+
+    blocks
+}
+
+/// Returns the part of every mark which falls on a page, as ranges of the
+/// words of the page.
+fn get_page_marks(marks: &[Mark], start: usize, end: usize) -> Vec<Mark> {
+    marks
+        .iter()
+        .filter(|mark| mark.start < end && mark.end > start)
+        .map(|mark| Mark {
+            mark_type: mark.mark_type,
+            start: mark.start.max(start) - start,
+            end: mark.end.min(end) - start,
+            url: mark.url.clone(),
+        })
+        .collect()
+}
+
+/// Returns the phrases which fall on a page, as ranges of the words of the
+/// page.
+pub fn get_page_phrases(phrases: &[PhraseSpan], start: usize, end: usize) -> Vec<PhraseSpan> {
+    phrases
+        .iter()
+        .filter(|span| span.start >= start && span.end <= end)
+        .map(|span| PhraseSpan {
+            phrase: span.phrase.clone(),
+            start: span.start - start,
+            end: span.end - start,
+        })
+        .collect()
+}
+
+/// Splits the words into the pages starting at the page starts. Every page
+/// comes with the blocks and marks on it.
+pub fn get_page_data(
+    words: &[String],
+    paragraph_indices: &[i32],
+    block_types: &[String],
+    marks: &[Mark],
+    page_starts: &[i32],
+) -> serde_json::Value {
+    let mut pages: Vec<&[String]> = vec![];
+    let mut blocks: Vec<Vec<serde_json::Value>> = vec![];
+    let mut page_marks: Vec<Vec<Mark>> = vec![];
+
+    for page_index in 0..page_starts.len() {
+        let page = get_page(
+            words.len(),
+            paragraph_indices,
+            block_types,
+            marks,
+            page_starts,
+            page_index,
+        )
+        .unwrap();
+
+        pages.push(&words[page.start..page.end]);
+        blocks.push(page.blocks);
+        page_marks.push(page.marks);
+    }
+
+    json!({
+        "pages": pages,
+        "blocks": blocks,
+        "marks": page_marks,
+        "pageStarts": page_starts,
+    })
 }
 
 #[cfg(test)]
@@ -290,7 +1354,7 @@ mod tests {
 
     #[test]
     fn english_word_split_1() {
-        let words = get_words_english_slice(
+        let words = EnglishTokenizer.get_words(
             r#"
             Chapter 1
 
@@ -316,7 +1380,14 @@ mod tests {
         In my younger and more vulnerable years. My father gave me some advice that I've been turning. Over in my mind ever since. "Whenever you feel like criticizing any one," he. Told me, "just remember that all the people in this world haven't had the advantages that you've had."
         "#;
 
-        get_sentences(text, &get_words_english_owned(text)[..]);
+        let words = get_words_and_base_forms(text, &EnglishTokenizer, None).0;
+        let token_data = get_token_data(text, &words[..], &EnglishTokenizer);
+        get_sentences(
+            text,
+            &words[..],
+            &token_data.paragraph_indices[..],
+            &EnglishTokenizer,
+        );
     }
 
     #[test]
@@ -325,6 +1396,1043 @@ mod tests {
         你好，这是一个“测试句子”。它会不会知道？你之前跟，我说“怎么办呢？”我也不知道怎么回答。哈哈哈
         "#;
 
-        println!("{:?}", get_sentences(text, &get_words_english_owned(text)[..]));
+        let words = get_words_and_base_forms(text, &EnglishTokenizer, None).0;
+        let token_data = get_token_data(text, &words[..], &EnglishTokenizer);
+        println!(
+            "{:?}",
+            get_sentences(
+                text,
+                &words[..],
+                &token_data.paragraph_indices[..],
+                &EnglishTokenizer
+            )
+        );
+    }
+
+    #[test]
+    fn chinese_sentence_split_2() {
+        let text = "你之前跟我说“怎么办呢？”我也不知道。哈哈哈";
+        let tokenizer = get_tokenizer("zh").unwrap();
+        let words = get_words_and_base_forms(text, tokenizer, None).0;
+        let token_data = get_token_data(text, &words[..], tokenizer);
+        let (sentences, sentence_stops) = get_sentences(
+            text,
+            &words[..],
+            &token_data.paragraph_indices[..],
+            tokenizer,
+        )
+        .unwrap();
+
+        assert_eq!(sentences.len(), 3);
+        assert_eq!(sentences[0].last(), Some(&"”"));
+        assert_eq!(sentences[2].concat(), "哈哈哈");
+        assert_eq!(sentence_stops.len(), 4);
+    }
+
+    #[test]
+    fn word_sentence_1() {
+        let text = "I like tea. It is green!\nGood.";
+        let tokenizer = get_tokenizer("en").unwrap();
+        let words = get_words_and_base_forms(text, tokenizer, None).0;
+        let token_data = get_token_data(text, &words[..], tokenizer);
+        let (sentences, sentence_stops) = get_sentences(
+            text,
+            &words[..],
+            &token_data.paragraph_indices[..],
+            tokenizer,
+        )
+        .unwrap();
+        let sentences = serde_json::to_value(sentences).unwrap();
+
+        let green = words.iter().position(|word| word == "green").unwrap();
+        assert_eq!(
+            get_word_sentence(&sentences, &sentence_stops, green),
+            Some(String::from("It is green!"))
+        );
+        assert_eq!(
+            get_word_sentence(&sentences, &sentence_stops, 0),
+            Some(String::from("I like tea."))
+        );
+        assert_eq!(
+            get_word_sentence(&sentences, &sentence_stops, words.len()),
+            None
+        );
+    }
+
+    const SENTENCE_PIECES: &[&str] = &[
+        "你好",
+        "测试",
+        "句子",
+        "日本語",
+        "勉強",
+        "한국어",
+        "공부",
+        "hello",
+        "world",
+        "l'été",
+        "42",
+        " ",
+        "  ",
+        "\n",
+        "\n\n",
+        "。",
+        "！",
+        "？",
+        ".",
+        "!",
+        "?",
+        "，",
+        ",",
+        "“",
+        "”",
+        "「",
+        "」",
+        "『",
+        "』",
+        "\"",
+        "(",
+        ")",
+        "と",
+        "っ",
+        "…",
+    ];
+
+    #[test]
+    fn sentence_alignment_property_1() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(9);
+
+        for lang in &["en", "zh", "ja", "ko", "fr"] {
+            let tokenizer = get_tokenizer(lang).unwrap();
+
+            for _ in 0..200 {
+                let text = (0..rng.gen_range(0, 40))
+                    .map(|_| SENTENCE_PIECES[rng.gen_range(0, SENTENCE_PIECES.len())])
+                    .collect::<String>();
+                let words = get_words_and_base_forms(&text, tokenizer, None).0;
+                let token_data = get_token_data(&text, &words[..], tokenizer);
+                let (sentences, sentence_stops) =
+                    get_sentences(&text, &words[..], &token_data.paragraph_indices, tokenizer)
+                        .unwrap();
+
+                // every word lands in exactly one sentence, in order
+                assert_eq!(sentences.concat(), words, "{:?} in {}", text, lang);
+                assert!(sentences.iter().all(|sentence| !sentence.is_empty()));
+
+                assert_eq!(sentence_stops.len(), sentences.len() + 1);
+                assert_eq!(sentence_stops[0], 0);
+                assert_eq!(*sentence_stops.last().unwrap() as usize, words.len());
+                for (index, sentence) in sentences.iter().enumerate() {
+                    assert_eq!(
+                        (sentence_stops[index + 1] - sentence_stops[index]) as usize,
+                        sentence.len()
+                    );
+
+                    // and no sentence spans two paragraphs
+                    let start = sentence_stops[index] as usize;
+                    let end = sentence_stops[index + 1] as usize;
+                    let paragraph = token_data.paragraph_indices[start];
+                    assert!(token_data.paragraph_indices[start..end]
+                        .iter()
+                        .all(|index| *index == paragraph));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn word_offsets_1() {
+        let text = "Hello, world. 你好";
+        let words = get_words_and_base_forms(text, &EnglishTokenizer, None).0;
+        let offsets = get_word_offsets(text, &words[..]);
+
+        assert_eq!(offsets.len(), words.len());
+        for (word, offset) in words.iter().zip(offsets) {
+            assert_eq!(&text[offset..offset + word.len()], word);
+        }
+    }
+
+    #[test]
+    fn token_data_1() {
+        let text = "Café costs 3.50 at https://example.com/menu.\n\n你好，世界";
+        let words = [
+            "Café",
+            " ",
+            "costs",
+            " ",
+            "3.50",
+            " ",
+            "at",
+            " ",
+            "https",
+            ":",
+            "/",
+            "/",
+            "example.com",
+            "/",
+            "menu",
+            ".",
+            "\n\n",
+            "你好",
+            "，",
+            "世界",
+        ]
+        .iter()
+        .map(|word| word.to_string())
+        .collect::<Vec<String>>();
+        let token_data = get_token_data(text, &words[..], &EnglishTokenizer);
+
+        assert_eq!(token_data.byte_offsets[2], 6);
+        assert_eq!(token_data.char_offsets[2], 5);
+        assert_eq!(token_data.char_offsets[17], 46);
+        assert_eq!(
+            token_data.token_types[..6],
+            [
+                "word",
+                "whitespace",
+                "word",
+                "whitespace",
+                "number",
+                "whitespace"
+            ]
+        );
+        assert!(token_data.token_types[8..15]
+            .iter()
+            .all(|token_type| token_type == "url"));
+        assert_eq!(token_data.token_types[15], "punctuation");
+        assert_eq!(token_data.paragraph_indices[16], 0);
+        assert_eq!(token_data.paragraph_indices[17], 1);
+        assert_eq!(token_data.block_types, ["paragraph", "paragraph"]);
+    }
+
+    #[test]
+    fn markdown_content_1() {
+        let content = "# The Title\n\nSome *light* reading with [a link](https://example.com).\n\n\
+                       - one item\n- another **item**\n\n> a quote\n\n```\nlet x = 1;\n```\n\nThe end.";
+        let content_text = get_content_text(content, "markdown").unwrap();
+
+        assert_eq!(
+            content_text.text,
+            "The Title\nSome light reading with a link.\none item\nanother item\na quote\nThe end.\n"
+        );
+
+        let words = get_words_and_base_forms(&content_text.text, &EnglishTokenizer, None).0;
+        let mut token_data = get_token_data(&content_text.text, &words[..], &EnglishTokenizer);
+        let marks = apply_markup(&content_text, &mut token_data);
+
+        assert!(words
+            .iter()
+            .all(|word| !["#", "*", "**", "[", "]", ">", "`"].contains(&&word[..])));
+        assert_eq!(
+            token_data.block_types,
+            [
+                "heading",
+                "paragraph",
+                "list_item",
+                "list_item",
+                "quote",
+                "paragraph"
+            ]
+        );
+
+        let mark_words = |mark: &Mark| words[mark.start..mark.end].concat();
+        assert_eq!(marks.len(), 3);
+        assert_eq!(marks[0].mark_type, MarkType::Emphasis);
+        assert_eq!(mark_words(&marks[0]), "light");
+        assert_eq!(marks[1].mark_type, MarkType::Link);
+        assert_eq!(mark_words(&marks[1]), "a link");
+        assert_eq!(marks[1].url.as_deref(), Some("https://example.com"));
+        assert_eq!(mark_words(&marks[2]), "item");
+
+        let page_data = get_page_data(
+            &words[..],
+            &token_data.paragraph_indices[..],
+            &token_data.block_types[..],
+            &marks[..],
+            &[0],
+        );
+        assert_eq!(
+            page_data["marks"][0][1]["url"],
+            json!("https://example.com")
+        );
+        assert!(page_data["marks"][0][0].get("url").is_none());
+
+        let plain_text = get_content_text(content, "plain").unwrap();
+        assert_eq!(plain_text.text, content);
+        assert!(plain_text.blocks.is_none());
+        assert!(get_content_text(content, "html").is_err());
+    }
+
+    #[test]
+    fn block_pages_1() {
+        let sentence = "This is a sentence of words. ";
+        let text = format!(
+            "# A Title\nChapter 1\n{}\n> {}\n- {}\n{}\n{}",
+            sentence.repeat(3),
+            sentence,
+            sentence,
+            sentence.repeat(6),
+            sentence.repeat(4),
+        );
+        let words = get_words_and_base_forms(&text, &EnglishTokenizer, None).0;
+        let token_data = get_token_data(&text, &words[..], &EnglishTokenizer);
+
+        assert_eq!(
+            token_data.block_types,
+            [
+                "heading",
+                "heading",
+                "paragraph",
+                "quote",
+                "list_item",
+                "paragraph",
+                "paragraph"
+            ]
+        );
+
+        let (_, sentence_stops) = get_sentences(
+            &text,
+            &words[..],
+            &token_data.paragraph_indices[..],
+            &EnglishTokenizer,
+        )
+        .unwrap();
+        let get_page_starts_at = |size: i32, unit: &str| {
+            get_page_starts(
+                &words[..],
+                &token_data.token_types[..],
+                &sentence_stops[..],
+                &token_data.paragraph_indices[..],
+                &token_data.block_types[..],
+                PageSize::new(size, unit).unwrap(),
+            )
+        };
+
+        for (size, unit) in &[(40, "words"), (10, "words"), (150, "chars")] {
+            let page_starts = get_page_starts_at(*size, unit);
+            let page_data = get_page_data(
+                &words[..],
+                &token_data.paragraph_indices[..],
+                &token_data.block_types[..],
+                &[],
+                &page_starts[..],
+            );
+            let pages = page_data["pages"].as_array().unwrap();
+            let blocks = page_data["blocks"].as_array().unwrap();
+            assert_eq!(pages.len(), page_starts.len());
+            assert_eq!(pages.len(), blocks.len());
+
+            // pages start at sentence starts and hold every word once
+            assert!(page_starts
+                .iter()
+                .all(|start| sentence_stops.contains(start)));
+            let page_words = pages
+                .iter()
+                .flat_map(|page| page.as_array().unwrap())
+                .map(|word| word.as_str().unwrap())
+                .collect::<Vec<&str>>();
+            assert_eq!(page_words, words);
+
+            for (page, page_blocks) in pages.iter().zip(blocks) {
+                let page_blocks = page_blocks.as_array().unwrap();
+                assert_eq!(page_blocks[0]["start"], json!(0));
+                assert_eq!(
+                    page_blocks.last().unwrap()["end"],
+                    json!(page.as_array().unwrap().len())
+                );
+            }
+
+            // and the same word is found on the page holding it
+            for word_index in &[0, 30, 100, words.len() as i32 - 1] {
+                let page_index = get_page_index(&page_starts[..], *word_index);
+                assert!(page_starts[page_index] <= *word_index);
+                assert!(
+                    page_index + 1 == page_starts.len()
+                        || *word_index < page_starts[page_index + 1]
+                );
+            }
+        }
+
+        // pages of 40 words end at the end of the list item and of the long
+        // paragraph instead of filling up to the middle of the next one
+        let page_starts = get_page_starts_at(40, "words");
+        assert_eq!(page_starts.len(), 3);
+        assert_eq!(token_data.paragraph_indices[page_starts[1] as usize], 5);
+        assert_eq!(token_data.paragraph_indices[page_starts[2] as usize], 6);
+
+        // and pages of 10 words don't end right after a heading
+        let page_starts = get_page_starts_at(10, "words");
+        for page_start in &page_starts[1..] {
+            let paragraph = token_data.paragraph_indices[*page_start as usize - 1];
+            assert_ne!(token_data.block_types[paragraph as usize], "heading");
+        }
+
+        assert!(PageSize::new(5, "words").is_none());
+        assert!(PageSize::new(100, "pages").is_none());
+    }
+
+    #[test]
+    fn block_pages_2() {
+        let text = "This is a sentence of words. ".repeat(20);
+        let words = get_words_and_base_forms(&text, &EnglishTokenizer, None).0;
+        let token_data = get_token_data(&text, &words[..], &EnglishTokenizer);
+        let (_, sentence_stops) = get_sentences(
+            &text,
+            &words[..],
+            &token_data.paragraph_indices[..],
+            &EnglishTokenizer,
+        )
+        .unwrap();
+        let get_page_starts_with = |token_types: &[String], unit: &str| {
+            get_page_starts(
+                &words[..],
+                token_types,
+                &sentence_stops[..],
+                &token_data.paragraph_indices[..],
+                &token_data.block_types[..],
+                PageSize::new(100, unit).unwrap(),
+            )
+        };
+
+        // token types shorter than the words are measured in characters
+        let chars_page_starts = get_page_starts_with(&token_data.token_types[..], "chars");
+        assert!(chars_page_starts.len() > 1);
+        assert_eq!(get_page_starts_with(&[], "words"), chars_page_starts);
+        assert_eq!(
+            get_page_starts_with(&token_data.token_types[..10], "words"),
+            chars_page_starts
+        );
+
+        // and sentence stops past the last word add nothing
+        let mut long_stops = sentence_stops.clone();
+        long_stops.push(words.len() as i32 + 5);
+        get_page_starts(
+            &words[..],
+            &token_data.token_types[..],
+            &long_stops[..],
+            &token_data.paragraph_indices[..],
+            &token_data.block_types[..],
+            PageSize::new(100, "words").unwrap(),
+        );
+    }
+
+    #[test]
+    fn token_classes_1() {
+        let text = "Yesterday I met Anna in 2021. Anna wrote to anna.b@example.com, \
+                    and yesterday was fine.\nParis is big. I like Paris.";
+        let tokenizer = get_tokenizer("en").unwrap();
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, None);
+        let token_types = get_token_types(text, &words, tokenizer);
+        let type_of = |word: &str| {
+            let index = words.iter().position(|other| other == word).unwrap();
+            token_types[index].as_str()
+        };
+
+        assert_eq!(type_of("Yesterday"), "word");
+        assert_eq!(type_of("I"), "word");
+        assert_eq!(type_of("2021"), "number");
+        assert!(words
+            .iter()
+            .zip(&token_types)
+            .filter(|(word, _)| *word == "Anna" || *word == "Paris")
+            .all(|(_, token_type)| token_type == "proper_noun"));
+        assert!(words
+            .iter()
+            .zip(&token_types)
+            .filter(|(word, _)| word.contains('@') || *word == "example.com")
+            .all(|(_, token_type)| token_type == "email"));
+
+        let (unique_words, total_word_count, word_index_map, stop_word_map) =
+            get_article_main_data(&words, &base_forms, &token_types);
+        assert!(unique_words.get("anna").is_none());
+        assert!(unique_words.get("2021").is_none());
+        assert_eq!(unique_words["yesterday"], json!(2));
+        assert_eq!(total_word_count, 14);
+        assert!(word_index_map.get("paris").is_none());
+        assert_eq!(stop_word_map[(words.len() - 2).to_string()], json!(true));
+
+        let text = "Der Hund sieht den Mann.";
+        let tokenizer = get_tokenizer("de").unwrap();
+        let words = get_words_and_base_forms(text, tokenizer, None).0;
+        assert!(!get_token_types(text, &words, tokenizer).contains(&"proper_noun".to_string()));
+
+        let text = "我买了一个iPhone。";
+        let tokenizer = get_tokenizer("zh").unwrap();
+        let words = get_words_and_base_forms(text, tokenizer, None).0;
+        let token_types = get_token_types(text, &words, tokenizer);
+        let index = words.iter().position(|word| word == "iPhone").unwrap();
+        assert_eq!(token_types[index], "foreign");
+        assert_eq!(token_types[0], "word");
+    }
+
+    #[test]
+    fn phrases_1() {
+        let text = "She gave up smoking. He was looking forward to it, but I look up.";
+        let tokenizer = get_tokenizer("en").unwrap();
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, get_lemmatizer("en"));
+        let token_types = get_token_types(text, &words, tokenizer);
+        let user_phrases = vec![get_phrase_key("Smoking.", "en").unwrap()];
+
+        let spans = get_phrase_spans(&words, &base_forms, &token_types, "en", &user_phrases);
+        let found = spans
+            .iter()
+            .map(|span| (span.phrase.as_str(), words[span.start..span.end].concat()))
+            .collect::<Vec<(&str, String)>>();
+        assert_eq!(
+            found,
+            vec![
+                ("give up", "gave up".to_string()),
+                ("look forward to", "looking forward to".to_string()),
+                ("look up", "look up".to_string()),
+            ]
+        );
+        assert_eq!(
+            get_phrase_key("Gave  up", "en"),
+            Some("give up".to_string())
+        );
+        assert_eq!(get_phrase_key("...", "en"), None);
+
+        let page_phrases = get_page_phrases(&spans, spans[1].start, words.len());
+        assert_eq!(page_phrases.len(), 2);
+        assert_eq!(page_phrases[0].start, 0);
+
+        // chengyu jieba knows are single words already, a user's phrase
+        // spanning words is matched in either script
+        let text = "他做事總是馬馬虎虎。";
+        let tokenizer = get_tokenizer("zh-TW").unwrap();
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, None);
+        let token_types = get_token_types(text, &words, tokenizer);
+        let user_phrases = vec![get_phrase_key("总是马马虎虎", "zh").unwrap()];
+        let spans = get_phrase_spans(&words, &base_forms, &token_types, "zh-TW", &user_phrases);
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].phrase, "總是馬馬虎虎");
+        assert_eq!(
+            words[spans[0].start..spans[0].end].concat(),
+            spans[0].phrase
+        );
+    }
+
+    fn get_token_types(text: &str, words: &[String], tokenizer: &dyn Tokenizer) -> Vec<String> {
+        get_token_data(text, words, tokenizer).token_types
+    }
+
+    #[test]
+    fn page_word_maps_1() {
+        let text = "One more page. Then one more.";
+        let (words, base_forms) = get_words_and_base_forms(text, &EnglishTokenizer, None);
+        let token_data = get_token_data(text, &words[..], &EnglishTokenizer);
+        let (_, _, word_index_map, stop_word_map) =
+            get_article_main_data(&words[..], &base_forms[..], &token_data.token_types[..]);
+        let page_starts = [0, 7];
+
+        let page = get_page(
+            words.len(),
+            &token_data.paragraph_indices[..],
+            &token_data.block_types[..],
+            &[],
+            &page_starts,
+            1,
+        )
+        .unwrap();
+        assert_eq!((page.start, page.end), (7, words.len()));
+        assert_eq!(page.blocks[0]["end"], json!(words.len() - 7));
+        assert!(get_page(words.len(), &[], &[], &[], &page_starts, 2).is_none());
+
+        let (page_word_index_map, page_stop_word_map) =
+            get_page_word_maps(&word_index_map, &stop_word_map, page.start, page.end);
+        assert_eq!(page_word_index_map["one"], json!([9]));
+        assert_eq!(page_word_index_map["more"], json!([11]));
+        assert!(page_word_index_map.get("page").is_none());
+        assert!(page_stop_word_map
+            .as_object()
+            .unwrap()
+            .keys()
+            .all(|index| index.parse::<usize>().unwrap() >= 7));
+    }
+
+    #[test]
+    fn difficulty_1() {
+        let get_text_difficulty = |text: &str, lang: &str| {
+            let tokenizer = get_tokenizer(lang).unwrap();
+            let (words, base_forms) =
+                get_words_and_base_forms(text, tokenizer, get_lemmatizer(lang));
+            let token_data = get_token_data(text, &words[..], tokenizer);
+
+            get_difficulty(
+                &words[..],
+                &base_forms[..],
+                &token_data.token_types[..],
+                lang,
+            )
+        };
+
+        let easy = get_text_difficulty("我喜欢喝茶。你喜欢吗？", "zh").unwrap();
+        let hard = get_text_difficulty("政府宣布了新的经济改革方案。", "zh").unwrap();
+        assert!((1.0..=2.0).contains(&easy));
+        assert!(hard > easy);
+
+        let easy = get_text_difficulty("My sister likes apples.", "en").unwrap();
+        let hard = get_text_difficulty("The ubiquitous rhetoric was spurious.", "en").unwrap();
+        assert!(hard > easy);
+
+        // words without a level are graded by how frequent they are
+        let common = difficulty::CHINESE.get_difficulty(["全国"].iter().copied());
+        let rare = difficulty::CHINESE.get_difficulty(["眼角"].iter().copied());
+        assert!(common.unwrap() < rare.unwrap());
+        assert_eq!(
+            difficulty::CHINESE.get_difficulty(["改革"].iter().copied()),
+            Some(5.0)
+        );
+
+        assert_eq!(get_text_difficulty("안녕하세요", "ko"), None);
+        assert_eq!(get_text_difficulty("...", "en"), None);
+    }
+
+    #[test]
+    fn language_detection_1() {
+        let detect = |text: &str| {
+            let detection = detect_language(text).unwrap();
+            (
+                detection.lang,
+                detection.confidence >= MIN_DETECTION_CONFIDENCE,
+            )
+        };
+
+        assert_eq!(
+            detect("In my younger and more vulnerable years my father gave me some advice."),
+            ("en", true)
+        );
+        assert_eq!(
+            detect("Le petit prince est un livre pour les enfants et pour les adultes."),
+            ("fr", true)
+        );
+        assert_eq!(
+            detect("El niño come una manzana en la cocina con su madre."),
+            ("es", true)
+        );
+        assert_eq!(
+            detect("Der Hund ist nicht groß, aber er ist sehr schnell und schön."),
+            ("de", true)
+        );
+        assert_eq!(
+            detect("O menino não está em casa, mas a mãe dele está."),
+            ("pt", true)
+        );
+        assert_eq!(
+            detect("Il ragazzo è andato al mercato con la sua amica per comprare della frutta."),
+            ("it", true)
+        );
+        assert_eq!(detect("我们今天去学校学习汉语。"), ("zh-CN", true));
+        assert_eq!(detect("我們今天去學校學習漢語。"), ("zh-TW", true));
+        assert_eq!(detect("私は毎日日本語を勉強しています。"), ("ja", true));
+        assert_eq!(detect("저는 매일 한국어를 공부합니다."), ("ko", true));
+
+        // mixed scripts and Latin text without function words are left unsure
+        assert!(!detect("hello world 你好世界").1);
+        assert!(!detect("Apple banana cherry").1);
+        assert!(detect_language("123 !?").is_none());
+    }
+
+    #[test]
+    fn unsupported_language_1() {
+        assert!(get_tokenizer("en").is_ok());
+        assert!(get_tokenizer("zh-TW").is_ok());
+        assert!(get_tokenizer("xx").is_err());
+
+        assert_eq!(
+            get_or_query_string(&Some("hello".to_string()), &Some("xx".to_string())),
+            Err("unsupported")
+        );
+    }
+
+    #[test]
+    fn japanese_word_split_1() {
+        let tokens = JapaneseTokenizer.get_tokens("私は毎日日本語を勉強しています。");
+        let words = tokens.iter().map(|token| token.text).collect::<Vec<&str>>();
+
+        assert_eq!(
+            words,
+            vec![
+                "私",
+                "は",
+                "毎日",
+                "日本語",
+                "を",
+                "勉強",
+                "し",
+                "て",
+                "い",
+                "ます",
+                "。"
+            ]
+        );
+        assert_eq!(tokens[6].base_form, Some("する".to_string()));
+        assert_eq!(tokens[8].base_form, Some("いる".to_string()));
+        assert_eq!(tokens[0].base_form, None);
+    }
+
+    #[test]
+    fn japanese_base_form_1() {
+        let text = "昨日寿司を食べました。今日も食べたい。明日は食べない！";
+        let tokenizer = get_tokenizer("ja").unwrap();
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, None);
+        let (unique_words, _, _, _) = get_article_main_data(
+            &words,
+            &base_forms,
+            &get_token_types(text, &words, tokenizer),
+        );
+
+        assert_eq!(unique_words["食べる"], json!(3));
+        assert!(unique_words.get("食べ").is_none());
+    }
+
+    #[test]
+    fn japanese_sentence_split_1() {
+        let text = "「行こう。」と彼は言った。それから家に帰りました！\n「はい。」";
+
+        assert_eq!(
+            JapaneseTokenizer.get_sentences(text),
+            vec![
+                "「行こう。」と彼は言った。",
+                "それから家に帰りました！\n",
+                "「はい。」"
+            ]
+        );
+
+        let (words, _) = get_words_and_base_forms(text, &JapaneseTokenizer, None);
+        let token_data = get_token_data(text, &words[..], &JapaneseTokenizer);
+        let (_, sentence_stops) = get_sentences(
+            text,
+            &words[..],
+            &token_data.paragraph_indices[..],
+            &JapaneseTokenizer,
+        )
+        .unwrap();
+        assert_eq!(sentence_stops.len(), 4);
+    }
+
+    #[test]
+    fn korean_word_split_1() {
+        let text = "저는 학교에 갔어요. 학교는 커요! 친구들에게 공부했다고 말했어요.";
+        let tokenizer = get_tokenizer("ko").unwrap();
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, None);
+        let (unique_words, _, _, _) = get_article_main_data(
+            &words,
+            &base_forms,
+            &get_token_types(text, &words, tokenizer),
+        );
+
+        assert_eq!(words.concat(), text);
+        assert_eq!(&words[..4], &["저", "는", " ", "학교"]);
+        assert_eq!(unique_words["학교"], json!(2));
+        assert_eq!(unique_words["가다"], json!(1));
+        assert_eq!(unique_words["크다"], json!(1));
+        assert!(unique_words.get("학교에").is_none());
+        assert!(unique_words.get("친구들에게").is_none());
+    }
+
+    #[test]
+    fn korean_word_split_2() {
+        // a noun ending like a particle, as 고양이 does with 이, isn't cut short
+        assert_eq!(
+            get_non_space_words("고양이가 길에서", "ko"),
+            vec!["고양이", "가", "길", "에서"]
+        );
+        assert_eq!(KoreanTokenizer.get_words("고양이"), vec!["고양이"]);
+    }
+
+    #[test]
+    fn korean_base_form_1() {
+        assert_eq!(get_base_form("학교에서는", "ko"), Some("학교".to_string()));
+        assert_eq!(get_base_form("먹었다", "ko"), Some("먹다".to_string()));
+        assert_eq!(get_base_form("아이", "ko"), None);
+        assert_eq!(get_base_form("running", "en"), None);
+    }
+
+    fn get_non_space_words(text: &str, lang: &str) -> Vec<String> {
+        let tokenizer = get_tokenizer(lang).unwrap();
+
+        tokenizer
+            .get_words(text)
+            .into_iter()
+            .filter(|word| !word.trim().is_empty())
+            .map(|word| word.to_string())
+            .collect::<Vec<String>>()
+    }
+
+    #[test]
+    fn french_word_split_1() {
+        let words = get_non_space_words(
+            "L'homme a dit « Donne-moi l'arc-en-ciel », a-t-il ajouté. Rendez-vous aujourd'hui.",
+            "fr",
+        );
+
+        assert_eq!(
+            words,
+            vec![
+                "L'",
+                "homme",
+                "a",
+                "dit",
+                "«",
+                "Donne",
+                "-",
+                "moi",
+                "l'",
+                "arc-en-ciel",
+                "»",
+                ",",
+                "a",
+                "-",
+                "t",
+                "-",
+                "il",
+                "ajouté",
+                ".",
+                "Rendez-vous",
+                "aujourd'hui",
+                "."
+            ]
+        );
+    }
+
+    #[test]
+    fn italian_word_split_1() {
+        let tokenizer = get_tokenizer("it").unwrap();
+        let (words, base_forms) =
+            get_words_and_base_forms("Nell'arte voglio farlo.", tokenizer, None);
+
+        assert_eq!(
+            words,
+            vec!["Nell'", "arte", " ", "voglio", " ", "far", "lo", "."]
+        );
+        assert_eq!(base_forms[5], Some("fare".to_string()));
+
+        // only known verbs are split, not nouns ending in a pronoun
+        assert_eq!(
+            get_non_space_words("Marte è un verme.", "it"),
+            vec!["Marte", "è", "un", "verme", "."]
+        );
+        let (words, base_forms) = get_words_and_base_forms("Stiamo parlandoci.", tokenizer, None);
+        assert_eq!(words[2..4], ["parlando", "ci"]);
+        assert_eq!(base_forms[2], Some("parlare".to_string()));
+    }
+
+    #[test]
+    fn spanish_word_split_1() {
+        let tokenizer = get_tokenizer("es").unwrap();
+        let text = "¡Dámelo! Estoy mirándolo en la otra parte.";
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, None);
+        let (unique_words, total_word_count, _, _) = get_article_main_data(
+            &words,
+            &base_forms,
+            &get_token_types(text, &words, tokenizer),
+        );
+
+        assert_eq!(&words[..4], &["¡", "Dá", "me", "lo"]);
+        assert_eq!(base_forms[1], Some("dar".to_string()));
+        assert!(words.contains(&"parte".to_string()));
+        assert_eq!(unique_words["mirar"], json!(1));
+        assert_eq!(total_word_count, 10);
+
+        // only known verbs are split, not nouns ending in a pronoun
+        assert_eq!(
+            get_non_space_words("El estandarte y el descarte.", "es"),
+            vec!["El", "estandarte", "y", "el", "descarte", "."]
+        );
+        let (words, base_forms) =
+            get_words_and_base_forms("Cómetelo y leyéndolo.", tokenizer, None);
+        assert_eq!(&words[..3], &["Cóme", "te", "lo"]);
+        assert_eq!(base_forms[0], Some("comer".to_string()));
+        assert_eq!(base_forms[6], Some("leer".to_string()));
+    }
+
+    #[test]
+    fn portuguese_and_german_word_split_1() {
+        assert_eq!(
+            get_non_space_words("Dá-me um copo d'água.", "pt"),
+            vec!["Dá", "-", "me", "um", "copo", "d'", "água", "."]
+        );
+        assert_eq!(
+            get_non_space_words("Gibt's eine E-Mail aus Baden-Württemberg?", "de"),
+            vec![
+                "Gibt",
+                "'s",
+                "eine",
+                "E-Mail",
+                "aus",
+                "Baden-Württemberg",
+                "?"
+            ]
+        );
+    }
+
+    #[test]
+    fn english_lemma_1() {
+        let text = "The children were running. A child runs and ran, then studies better.";
+        let tokenizer = get_tokenizer("en").unwrap();
+        let (words, base_forms) = get_words_and_base_forms(text, tokenizer, get_lemmatizer("en"));
+        let (unique_words, _, _, _) = get_article_main_data(
+            &words,
+            &base_forms,
+            &get_token_types(text, &words, tokenizer),
+        );
+
+        assert_eq!(unique_words["child"], json!(2));
+        assert_eq!(unique_words["run"], json!(3));
+        assert_eq!(unique_words["be"], json!(1));
+        assert_eq!(unique_words["study"], json!(1));
+        assert_eq!(unique_words["good"], json!(1));
+        assert_eq!(base_forms[0], None);
+
+        assert_eq!(get_lemma("Went", "en"), Some("go".to_string()));
+        assert_eq!(get_lemma("go", "en"), None);
+        assert_eq!(get_lemma("학교에", "ko"), Some("학교".to_string()));
+        assert!(get_lemmatizer("zh").is_none());
+    }
+
+    #[test]
+    fn english_lemma_2() {
+        let lemmas = [
+            ("hoping", "hope"),
+            ("heated", "heat"),
+            ("planned", "plan"),
+            ("employed", "employ"),
+            ("quizzes", "quiz"),
+            ("cookies", "cookie"),
+            ("flies", "fly"),
+            ("Glasses", "glass"),
+        ];
+        for (word, lemma) in &lemmas {
+            assert_eq!(get_lemma(word, "en"), Some(lemma.to_string()));
+        }
+
+        // words that only look inflected, or that aren't known, are kept
+        for word in &["pass", "news", "this", "thing", "xyzzying"] {
+            assert_eq!(get_lemma(word, "en"), None);
+        }
+    }
+
+    #[test]
+    fn chinese_script_merge_1() {
+        assert_eq!(get_script_variants("学习"), vec!["学习", "學習"]);
+        assert_eq!(get_script_variants("學習"), vec!["学习", "學習"]);
+        assert_eq!(get_script_variants("你好"), vec!["你好"]);
+        assert_eq!(get_script_variants("鸚鵡"), vec!["鹦鹉", "鸚鵡"]);
+        assert_eq!(get_script_variants("傢具"), vec!["家具"]);
+
+        let mut status_data = json!({
+            "en": { "learning": {}, "known": { "run": 1 } },
+            "zh": { "learning": { "学习": 2 }, "known": {} },
+            "zh-TW": { "learning": {}, "known": { "學習": 1, "電腦": 1 }, "ignored": { "王": 1 } }
+        });
+        let mut definition_data = json!({ "zh-TW": { "電腦": "computer" } });
+        let mut context_data = json!({
+            "zh-CN": { "学习": [{ "sentence": "我爱学习。", "article_id": 1 }] },
+            "zh-TW": { "學習": [{ "sentence": "我愛學習。", "article_id": 2 }] }
+        });
+        merge_chinese_word_data(&mut status_data, &mut definition_data, &mut context_data);
+
+        assert_eq!(status_data["en"]["known"]["run"], json!(1));
+        for lang in CHINESE_LANGS {
+            assert_eq!(status_data[*lang]["learning"]["學習"], json!(2));
+            assert_eq!(status_data[*lang]["ignored"]["王"], json!(1));
+            assert!(status_data[*lang]["known"].get("学习").is_none());
+            assert_eq!(status_data[*lang]["known"]["电脑"], json!(1));
+            assert_eq!(definition_data[*lang]["电脑"], json!("computer"));
+            assert_eq!(context_data[*lang]["學習"].as_array().unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn chinese_pinyin_1() {
+        let text = "我去银行取钱，他们在长城上走。我觉得睡觉很重要！";
+        let tokenizer = get_tokenizer("zh").unwrap();
+        let (words, _) = get_words_and_base_forms(text, tokenizer, None);
+        let pinyin = get_pinyin(&words, "zh").unwrap();
+        let get_reading = |word: &str| {
+            let index = words.iter().position(|w| w == word).unwrap();
+            pinyin[index].clone()
+        };
+
+        assert_eq!(get_reading("银行"), Some("yínháng".to_string()));
+        assert_eq!(get_reading("长城"), Some("chángchéng".to_string()));
+        assert_eq!(get_reading("觉得"), Some("juéde".to_string()));
+        assert_eq!(get_reading("睡觉"), Some("shuìjiào".to_string()));
+        assert_eq!(get_reading("，"), None);
+        assert_eq!(pinyin.len(), words.len());
+
+        assert_eq!(
+            get_pinyin(&["西安".to_string(), "學習".to_string()], "zh-TW"),
+            Some(vec![Some("Xī'ān".to_string()), Some("xuéxí".to_string())])
+        );
+        assert_eq!(
+            get_pinyin(&["女儿".to_string()], "zh").unwrap()[0],
+            Some("nǚ'ér".to_string())
+        );
+        assert!(get_pinyin(&["银行".to_string()], "ja").is_none());
+
+        // characters with several readings are read by the word they're in
+        let words = ["长大", "重新", "行业", "得到"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            get_pinyin(&words, "zh"),
+            Some(vec![
+                Some("zhǎngdà".to_string()),
+                Some("chóngxīn".to_string()),
+                Some("hángyè".to_string()),
+                Some("dédào".to_string())
+            ])
+        );
+
+        // characters missing from the dictionary are read one by one, and the
+        // ones without any reading are kept in a partial reading
+        assert_eq!(
+            get_pinyin(&["鹦鹉".to_string(), "𠮷野".to_string()], "zh"),
+            Some(vec![Some("yīngwǔ".to_string()), Some("𠮷yě".to_string())])
+        );
+    }
+
+    #[test]
+    fn chinese_custom_words_1() {
+        let text = "我今天蓝瘦香菇了";
+        let custom_words = vec![CustomWord {
+            word: "蓝瘦香菇",
+            freq: Some(1000),
+        }];
+
+        assert!(!get_non_space_words(text, "zh").contains(&"蓝瘦香菇".to_string()));
+        assert!(get_custom_tokenizer("en", &custom_words).is_none());
+        assert!(get_custom_tokenizer("zh", &[]).is_none());
+
+        let tokenizer = get_custom_tokenizer("zh", &custom_words).unwrap();
+        assert!(tokenizer.get_words(text).contains(&"蓝瘦香菇"));
+        assert!(!get_non_space_words(text, "zh").contains(&"蓝瘦香菇".to_string()));
+
+        // other users' words don't change the segmentation
+        let other_words = vec![CustomWord {
+            word: "今天蓝",
+            freq: None,
+        }];
+        let tokenizer = get_custom_tokenizer("zh", &other_words).unwrap();
+        assert_eq!(tokenizer.get_words(text).concat(), text);
+        assert!(tokenizer.get_words(text).contains(&"今天蓝"));
+        let tokenizer = get_custom_tokenizer("zh", &custom_words).unwrap();
+        assert!(tokenizer.get_words(text).contains(&"蓝瘦香菇"));
+        assert!(!tokenizer.get_words(text).contains(&"今天蓝"));
+
+        // and a word is left to the shared dictionary when its frequency is
+        // too low for jieba to join it
+        let rare_words = vec![CustomWord {
+            word: "今天蓝",
+            freq: Some(0),
+        }];
+        let tokenizer = get_custom_tokenizer("zh", &rare_words).unwrap();
+        assert_eq!(tokenizer.get_words(text), get_non_space_words(text, "zh"));
     }
 }
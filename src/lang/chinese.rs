@@ -0,0 +1,163 @@
+use super::sentence::{split_sentences, SentenceRules};
+use super::{is_latin_word, CustomWord, Tokenizer};
+use jieba_rs::Jieba;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+
+// "traditional simplified" character pairs, from ICU's Traditional-Simplified
+// transform. Several traditional characters can share a simplified one, the
+// first listed is used to convert back, and a character listed as its own
+// traditional form, like 家 for 傢, is kept as it is.
+const TS_CHARACTERS_TXT: &str = include_str!("../../data/zh/ts_characters.txt");
+
+fn get_character_pairs() -> impl Iterator<Item = (char, char)> {
+    TS_CHARACTERS_TXT.lines().filter_map(|line| {
+        let mut chars = line.chars();
+        let traditional = chars.next()?;
+        let simplified = chars.nth(1)?;
+        Some((traditional, simplified))
+    })
+}
+
+lazy_static! {
+    static ref JIEBA: Jieba = Jieba::new();
+    static ref TO_SIMPLIFIED: HashMap<char, char> = get_character_pairs().collect();
+    static ref TO_TRADITIONAL: HashMap<char, char> = {
+        let mut to_traditional: HashMap<char, char> = HashMap::new();
+        for (traditional, simplified) in get_character_pairs() {
+            to_traditional.entry(simplified).or_insert(traditional);
+        }
+        to_traditional
+    };
+}
+
+fn convert(text: &str, table: &HashMap<char, char>) -> String {
+    text.chars()
+        .map(|c| *table.get(&c).unwrap_or(&c))
+        .collect::<String>()
+}
+
+pub fn to_simplified(text: &str) -> String {
+    convert(text, &TO_SIMPLIFIED)
+}
+
+pub fn to_traditional(text: &str) -> String {
+    convert(text, &TO_TRADITIONAL)
+}
+
+// a terminator inside quotes or a book title doesn't end the sentence, so
+// 他说“怎么办呢？”我也不知道 splits after the closing quote
+static SENTENCE_RULES: SentenceRules = SentenceRules {
+    terminators: &['。', '！', '？', '!', '?'],
+    quote_opens: &['“', '‘', '「', '『', '《', '（'],
+    quote_closes: &['”', '’', '」', '』', '》', '）'],
+    continuations: &[],
+};
+
+/// The words of a user's own dictionary which are kept whole wherever they're
+/// found, before the text around them is segmented with the shared dictionary.
+struct CustomWords {
+    words: HashSet<String>,
+    // in characters
+    max_length: usize,
+}
+
+impl CustomWords {
+    /// Keeps the words whose frequency is high enough for jieba to join them,
+    /// and every word without one.
+    fn new(words: &[CustomWord]) -> CustomWords {
+        let words = words
+            .iter()
+            .filter(|word| match word.freq {
+                Some(freq) => freq >= JIEBA.suggest_freq(word.word),
+                None => true,
+            })
+            .map(|word| word.word.to_string())
+            .collect::<HashSet<String>>();
+        let max_length = words
+            .iter()
+            .map(|word| word.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        CustomWords { words, max_length }
+    }
+
+    /// Splits the text at the longest custom word starting at each character,
+    /// leftmost first, and cuts the text between them with the shared
+    /// dictionary.
+    fn cut<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut boundaries = text
+            .char_indices()
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>();
+        boundaries.push(text.len());
+
+        let mut words: Vec<&'a str> = vec![];
+        let mut rest_start = 0;
+        let mut index = 0;
+
+        while index + 1 < boundaries.len() {
+            let start = boundaries[index];
+            let max_length = self.max_length.min(boundaries.len() - 1 - index);
+            let length = (1..=max_length).rev().find(|length| {
+                self.words
+                    .contains(&text[start..boundaries[index + length]])
+            });
+
+            match length {
+                Some(length) => {
+                    let end = boundaries[index + length];
+                    if rest_start < start {
+                        words.extend(JIEBA.cut(&text[rest_start..start], false));
+                    }
+                    words.push(&text[start..end]);
+                    rest_start = end;
+                    index += length;
+                }
+                None => index += 1,
+            }
+        }
+
+        if rest_start < text.len() {
+            words.extend(JIEBA.cut(&text[rest_start..], false));
+        }
+
+        words
+    }
+}
+
+/// Segments with the shared jieba dictionary, keeping a user's own words whole
+/// on top of it. The shared dictionary is never changed or copied.
+pub struct ChineseTokenizer {
+    custom_words: Option<CustomWords>,
+}
+
+impl ChineseTokenizer {
+    pub const fn new() -> ChineseTokenizer {
+        ChineseTokenizer { custom_words: None }
+    }
+
+    pub fn with_custom_words(words: &[CustomWord]) -> ChineseTokenizer {
+        ChineseTokenizer {
+            custom_words: Some(CustomWords::new(words)),
+        }
+    }
+}
+
+impl Tokenizer for ChineseTokenizer {
+    fn get_words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self.custom_words {
+            Some(ref custom_words) => custom_words.cut(text),
+            None => JIEBA.cut(text, false),
+        }
+    }
+
+    fn get_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        split_sentences(text, &SENTENCE_RULES)
+    }
+
+    fn is_foreign_word(&self, word: &str) -> bool {
+        is_latin_word(word)
+    }
+}
@@ -0,0 +1,195 @@
+use super::chinese;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+
+// The most common function words of each language written in Latin script,
+// which make up a large part of any text in it.
+const STOP_WORDS: &[(&str, &str)] = &[
+    (
+        "en",
+        "the of and to in is that it was for on are with as his they be at one have this \
+         from or had by but not what all were we when your can said there an which she do \
+         how their if will would been has who its",
+    ),
+    (
+        "fr",
+        "le la les de des du un une et est en que qui dans pour pas sur au avec ce il elle \
+         ne se plus par je nous vous mais ou son sa ses été être fait comme tout aux cette",
+    ),
+    (
+        "it",
+        "il lo la le gli di del della e è un una che per non con sono ma come questo anche \
+         nel alla più ho ha si ci mi sua suo era essere dei delle degli nella",
+    ),
+    (
+        "es",
+        "el la los las de del y en un una que es por con no para se lo su al más pero como \
+         está son fue ya muy también hay este esta yo me le",
+    ),
+    (
+        "pt",
+        "o a os as de do da dos das e em um uma que é por com não para se no na mais mas \
+         como está são foi ao também muito você eu ele ela isso",
+    ),
+    (
+        "de",
+        "der die das und ist nicht ein eine zu den von mit sich des auf für im dem auch es \
+         an als wie aus bei nach wird sind war ich sie er wir",
+    ),
+];
+
+// Letters which only appear in some of the languages, along with how much
+// each points to them.
+const LETTERS: &[(char, &str, f32)] = &[
+    ('ñ', "es", 2.0),
+    ('¿', "es", 2.0),
+    ('¡', "es", 2.0),
+    ('ã', "pt", 2.0),
+    ('õ', "pt", 2.0),
+    ('ç', "pt", 0.5),
+    ('ç', "fr", 0.5),
+    ('ß', "de", 2.0),
+    ('ä', "de", 1.0),
+    ('ö', "de", 1.0),
+    ('ü', "de", 1.0),
+    ('œ', "fr", 2.0),
+    ('è', "fr", 0.5),
+    ('è', "it", 0.5),
+    ('ê', "fr", 0.5),
+    ('ê', "pt", 0.5),
+    ('à', "fr", 0.5),
+    ('à', "it", 0.5),
+    ('ò', "it", 1.0),
+    ('ì', "it", 1.0),
+];
+
+lazy_static! {
+    static ref STOP_WORD_SETS: Vec<(&'static str, HashSet<&'static str>)> = STOP_WORDS
+        .iter()
+        .map(|(lang, words)| (*lang, words.split_whitespace().collect()))
+        .collect();
+}
+
+fn is_han(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c)
+}
+
+fn is_kana(c: char) -> bool {
+    ('\u{3040}'..='\u{30FF}').contains(&c)
+}
+
+fn is_hangul(c: char) -> bool {
+    ('\u{AC00}'..='\u{D7AF}').contains(&c) || ('\u{1100}'..='\u{11FF}').contains(&c)
+}
+
+/// Picks between the Chinese language codes by which script the characters
+/// that differ between simplified and traditional Chinese are written in.
+fn get_chinese_lang(text: &str) -> &'static str {
+    let count_changed = |converted: String| {
+        text.chars()
+            .zip(converted.chars())
+            .filter(|(c, converted)| c != converted)
+            .count()
+    };
+
+    let traditional_count = count_changed(chinese::to_simplified(text));
+    let simplified_count = count_changed(chinese::to_traditional(text));
+
+    if traditional_count > simplified_count {
+        "zh-TW"
+    } else if simplified_count > traditional_count {
+        "zh-CN"
+    } else {
+        "zh"
+    }
+}
+
+/// Splits the Latin script part of a text between the languages using it,
+/// by their function words and the letters only some of them have.
+fn get_latin_scores(text: &str) -> HashMap<&'static str, f32> {
+    let mut scores: HashMap<&'static str, f32> = HashMap::new();
+
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        for (lang, stop_words) in STOP_WORD_SETS.iter() {
+            if stop_words.contains(&word[..]) {
+                *scores.entry(lang).or_default() += 1.0;
+            }
+        }
+    }
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        for (letter, lang, weight) in LETTERS {
+            if c == *letter {
+                *scores.entry(lang).or_default() += weight;
+            }
+        }
+    }
+
+    scores
+}
+
+/// Returns how likely the text is to be in each supported language, most
+/// likely first, with the likelihoods adding up to 1. Returns nothing for a
+/// text without letters.
+pub fn get_language_scores(text: &str) -> Vec<(&'static str, f32)> {
+    let mut han_count = 0;
+    let mut kana_count = 0;
+    let mut hangul_count = 0;
+    let mut latin_count = 0;
+
+    for c in text.chars() {
+        if is_han(c) {
+            han_count += 1;
+        } else if is_kana(c) {
+            kana_count += 1;
+        } else if is_hangul(c) {
+            hangul_count += 1;
+        } else if c.is_alphabetic() && (c.is_ascii() || ('\u{C0}'..='\u{24F}').contains(&c)) {
+            latin_count += 1;
+        }
+    }
+
+    let letter_count = han_count + kana_count + hangul_count + latin_count;
+    if letter_count == 0 {
+        return vec![];
+    }
+
+    let mut counts: Vec<(&'static str, f32)> = vec![];
+
+    // kanji are counted as Japanese once kana make up a tenth of the text
+    if kana_count * 10 >= han_count + kana_count {
+        counts.push(("ja", (han_count + kana_count) as f32));
+    } else {
+        counts.push((get_chinese_lang(text), han_count as f32));
+        counts.push(("ja", kana_count as f32));
+    }
+
+    counts.push(("ko", hangul_count as f32));
+
+    // the scores are squared since the languages share many function words,
+    // which would otherwise leave even a clear text split between them
+    let latin_scores = get_latin_scores(text);
+    let latin_score_sum: f32 = latin_scores.values().map(|score| score * score).sum();
+    for (lang, _) in STOP_WORDS {
+        let share = if latin_score_sum > 0.0 {
+            latin_scores.get(lang).map_or(0.0, |score| score * score) / latin_score_sum
+        } else {
+            1.0 / STOP_WORDS.len() as f32
+        };
+
+        counts.push((lang, latin_count as f32 * share));
+    }
+
+    let mut scores = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 0.0)
+        .map(|(lang, count)| (lang, count / letter_count as f32))
+        .collect::<Vec<(&str, f32)>>();
+    scores.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+
+    scores
+}
@@ -0,0 +1,140 @@
+use super::chinese;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+
+// "word level" lines of HSK 1 to 6
+const HSK_TXT: &str = include_str!("../../data/zh/hsk.txt");
+// one word per line, most frequent first
+const CHINESE_FREQUENCY_TXT: &str = include_str!("../../data/zh/frequency.txt");
+// "word level" lines of CEFR A1 to C1
+const CEFR_TXT: &str = include_str!("../../data/en/cefr.txt");
+// one word per line, most frequent first
+const ENGLISH_FREQUENCY_TXT: &str = include_str!("../../data/en/frequency.txt");
+
+const CEFR_LEVELS: &[&str] = &["A1", "A2", "B1", "B2", "C1", "C2"];
+
+/// The hardest level, given to the words none of the lists know.
+pub const MAX_LEVEL: u8 = 6;
+
+// the frequency rank a word has to be under to be given each level
+const RANK_LEVELS: &[usize] = &[500, 1000, 2000, 4000, 8000];
+
+/// Grades words from 1 to `MAX_LEVEL` using a list of learner levels, and
+/// the frequency of the words missing from it.
+pub struct DifficultyList {
+    levels: HashMap<&'static str, u8>,
+    ranks: HashMap<&'static str, usize>,
+    // the form words are looked up in, or None for words which aren't graded
+    normalize: fn(&str) -> Option<String>,
+}
+
+impl DifficultyList {
+    fn load(
+        level_text: &'static str,
+        frequency_text: Option<&'static str>,
+        parse_level: fn(&str) -> Option<u8>,
+        normalize: fn(&str) -> Option<String>,
+    ) -> DifficultyList {
+        let levels = level_text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split(' ');
+                Some((parts.next()?, parse_level(parts.next()?)?))
+            })
+            .collect::<HashMap<&str, u8>>();
+
+        let ranks = frequency_text
+            .unwrap_or("")
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .enumerate()
+            .map(|(rank, word)| (word, rank))
+            .collect::<HashMap<&str, usize>>();
+
+        DifficultyList {
+            levels,
+            ranks,
+            normalize,
+        }
+    }
+
+    fn get_level(&self, word: &str) -> u8 {
+        if let Some(level) = self.levels.get(word) {
+            return *level;
+        }
+
+        match self.ranks.get(word) {
+            Some(rank) => {
+                RANK_LEVELS
+                    .iter()
+                    .position(|max_rank| rank < max_rank)
+                    .unwrap_or(RANK_LEVELS.len()) as u8
+                    + 1
+            }
+            None => MAX_LEVEL,
+        }
+    }
+
+    /// Returns the mean level of the distinct words, from 1 to `MAX_LEVEL`,
+    /// or None if none of them are graded.
+    pub fn get_difficulty<'a>(&self, words: impl Iterator<Item = &'a str>) -> Option<f32> {
+        let unique_words = words
+            .filter_map(self.normalize)
+            .collect::<HashSet<String>>();
+
+        if unique_words.is_empty() {
+            return None;
+        }
+
+        let level_sum: u32 = unique_words
+            .iter()
+            .map(|word| u32::from(self.get_level(word)))
+            .sum();
+        let difficulty = level_sum as f32 / unique_words.len() as f32;
+
+        Some((difficulty * 100.0).round() / 100.0)
+    }
+}
+
+fn parse_number_level(level: &str) -> Option<u8> {
+    level.parse::<u8>().ok()
+}
+
+fn parse_cefr_level(level: &str) -> Option<u8> {
+    CEFR_LEVELS
+        .iter()
+        .position(|cefr_level| *cefr_level == level)
+        .map(|index| index as u8 + 1)
+}
+
+fn normalize_chinese(word: &str) -> Option<String> {
+    if word.chars().any(|c| ('\u{4E00}'..='\u{9FFF}').contains(&c)) {
+        Some(chinese::to_simplified(word))
+    } else {
+        None
+    }
+}
+
+fn normalize_english(word: &str) -> Option<String> {
+    if word.chars().any(char::is_alphabetic) {
+        Some(word.to_lowercase())
+    } else {
+        None
+    }
+}
+
+lazy_static! {
+    pub static ref CHINESE: DifficultyList = DifficultyList::load(
+        HSK_TXT,
+        Some(CHINESE_FREQUENCY_TXT),
+        parse_number_level,
+        normalize_chinese
+    );
+    pub static ref ENGLISH: DifficultyList = DifficultyList::load(
+        CEFR_TXT,
+        Some(ENGLISH_FREQUENCY_TXT),
+        parse_cefr_level,
+        normalize_english
+    );
+}
@@ -0,0 +1,19 @@
+use super::{is_capitalized, Tokenizer};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub struct EnglishTokenizer;
+
+impl Tokenizer for EnglishTokenizer {
+    fn get_words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        text.split_word_bounds().collect::<Vec<&str>>()
+    }
+
+    fn get_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        text.split_sentence_bounds().collect::<Vec<&str>>()
+    }
+
+    // the pronoun I is capitalized wherever it is
+    fn is_capitalized(&self, word: &str) -> bool {
+        is_capitalized(word) && word != "I" && !word.starts_with("I'") && !word.starts_with("I’")
+    }
+}
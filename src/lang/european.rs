@@ -0,0 +1,455 @@
+use super::{is_capitalized, Token, Tokenizer, STOP_CHARS};
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+const HYPHENS: &[&str] = &["-", "‐"];
+
+const SPANISH_VERBS_TXT: &str = include_str!("../../data/es/verbs.txt");
+const ITALIAN_VERBS_TXT: &str = include_str!("../../data/it/verbs.txt");
+
+lazy_static! {
+    static ref SPANISH_VERBS: HashSet<&'static str> = load_verbs(SPANISH_VERBS_TXT);
+    static ref ITALIAN_VERBS: HashSet<&'static str> = load_verbs(ITALIAN_VERBS_TXT);
+}
+
+fn load_verbs(text: &'static str) -> HashSet<&'static str> {
+    text.lines().filter(|line| !line.starts_with('#')).collect()
+}
+
+fn is_spanish_verb(infinitive: &str) -> bool {
+    SPANISH_VERBS.contains(infinitive)
+}
+
+fn is_italian_verb(infinitive: &str) -> bool {
+    ITALIAN_VERBS.contains(infinitive)
+}
+
+fn is_never_verb(_infinitive: &str) -> bool {
+    false
+}
+
+/// A verb ending enclitics attach to, along with the infinitive endings that
+/// may replace it, tried in order.
+struct HostRule {
+    ending: &'static str,
+    infinitive_endings: &'static [&'static str],
+}
+
+/// The rules for the romance and germanic languages, which all share unicode
+/// word boundaries but disagree on how articles and pronouns attach to words.
+pub struct EuropeanTokenizer {
+    // articles and pronouns that drop their vowel before another word, l'homme
+    elisions: &'static [&'static str],
+    // contractions attached to the end of the previous word, gibt's
+    trailing_elisions: &'static [&'static str],
+    // pronouns attached to a verb with a hyphen, donne-moi, dá-me
+    hyphen_clitics: &'static [&'static str],
+    // pronouns attached to a verb without a hyphen, dámelo, farlo
+    enclitics: &'static [&'static str],
+    // endings of the verb forms enclitics attach to, infinitives and gerunds
+    enclitic_hosts: &'static [HostRule],
+    // endings of the imperatives enclitics attach to, which only keep an
+    // accent because of the added syllables, dámelo
+    accented_enclitic_hosts: &'static [HostRule],
+    // words ending in a pronoun or host that are never verb and clitic
+    enclitic_exceptions: &'static [&'static str],
+    // whether an infinitive is a known verb, which enclitics can be split off
+    is_verb: fn(&str) -> bool,
+    // hyphenated words made of clitics that are still a single word
+    compound_exceptions: &'static [&'static str],
+    // every noun is capitalized, not just names, as in German
+    capitalized_nouns: bool,
+}
+
+pub static FRENCH: EuropeanTokenizer = EuropeanTokenizer {
+    elisions: &[
+        "l'", "d'", "j'", "m'", "t'", "s'", "n'", "c'", "qu'", "jusqu'", "lorsqu'", "puisqu'",
+        "quoiqu'", "presqu'",
+    ],
+    trailing_elisions: &[],
+    hyphen_clitics: &[
+        "moi", "toi", "lui", "elle", "il", "ils", "elles", "on", "nous", "vous", "le", "la", "les",
+        "leur", "y", "en", "ce", "t", "je", "tu",
+    ],
+    enclitics: &[],
+    enclitic_hosts: &[],
+    accented_enclitic_hosts: &[],
+    enclitic_exceptions: &[],
+    is_verb: is_never_verb,
+    compound_exceptions: &["rendez-vous", "va-et-vient", "vis-à-vis"],
+    capitalized_nouns: false,
+};
+
+pub static ITALIAN: EuropeanTokenizer = EuropeanTokenizer {
+    elisions: &[
+        "l'", "dell'", "dall'", "nell'", "sull'", "all'", "coll'", "un'", "d'", "c'", "quell'",
+        "quest'", "bell'", "sant'", "buon'", "nessun'", "tutt'", "v'", "m'", "t'", "s'",
+    ],
+    trailing_elisions: &[],
+    hyphen_clitics: &[],
+    enclitics: &[
+        "mi", "ti", "si", "ci", "vi", "lo", "la", "li", "le", "gli", "ne", "me", "te", "se", "ce",
+        "ve", "glielo", "gliela", "glieli", "gliele", "gliene",
+    ],
+    // infinitives drop their last letter before an enclitic, far(e)lo
+    enclitic_hosts: &[
+        HostRule {
+            ending: "ar",
+            infinitive_endings: &["are"],
+        },
+        HostRule {
+            ending: "er",
+            infinitive_endings: &["ere"],
+        },
+        HostRule {
+            ending: "ir",
+            infinitive_endings: &["ire"],
+        },
+        HostRule {
+            ending: "ando",
+            infinitive_endings: &["are"],
+        },
+        HostRule {
+            ending: "endo",
+            infinitive_endings: &["ere", "ire"],
+        },
+    ],
+    accented_enclitic_hosts: &[],
+    enclitic_exceptions: &["arte", "parte", "morte", "forte", "sorte", "carne", "perle"],
+    is_verb: is_italian_verb,
+    compound_exceptions: &[],
+    capitalized_nouns: false,
+};
+
+pub static SPANISH: EuropeanTokenizer = EuropeanTokenizer {
+    elisions: &[],
+    trailing_elisions: &[],
+    hyphen_clitics: &[],
+    enclitics: &[
+        "me", "te", "se", "le", "lo", "la", "nos", "os", "les", "los", "las",
+    ],
+    enclitic_hosts: &[
+        HostRule {
+            ending: "ar",
+            infinitive_endings: &["ar"],
+        },
+        HostRule {
+            ending: "er",
+            infinitive_endings: &["er"],
+        },
+        HostRule {
+            ending: "ir",
+            infinitive_endings: &["ir"],
+        },
+        HostRule {
+            ending: "ando",
+            infinitive_endings: &["ar"],
+        },
+        HostRule {
+            ending: "iendo",
+            infinitive_endings: &["er", "ir"],
+        },
+        // leyendo, huyendo
+        HostRule {
+            ending: "yendo",
+            infinitive_endings: &["er", "ir"],
+        },
+    ],
+    accented_enclitic_hosts: &[
+        HostRule {
+            ending: "a",
+            infinitive_endings: &["ar"],
+        },
+        HostRule {
+            ending: "e",
+            infinitive_endings: &["er", "ir"],
+        },
+    ],
+    enclitic_exceptions: &[
+        "arte", "parte", "suerte", "muerte", "norte", "fuerte", "perla", "perlas", "carlos",
+        "cortes", "partes", "artes",
+    ],
+    is_verb: is_spanish_verb,
+    compound_exceptions: &[],
+    capitalized_nouns: false,
+};
+
+pub static PORTUGUESE: EuropeanTokenizer = EuropeanTokenizer {
+    elisions: &["d'"],
+    trailing_elisions: &[],
+    hyphen_clitics: &[
+        "me", "te", "se", "lhe", "lhes", "o", "a", "os", "as", "lo", "la", "los", "las", "no",
+        "na", "nos", "nas", "vos",
+    ],
+    enclitics: &[],
+    enclitic_hosts: &[],
+    accented_enclitic_hosts: &[],
+    enclitic_exceptions: &[],
+    is_verb: is_never_verb,
+    compound_exceptions: &[],
+    capitalized_nouns: false,
+};
+
+pub static GERMAN: EuropeanTokenizer = EuropeanTokenizer {
+    elisions: &[],
+    trailing_elisions: &["'s"],
+    hyphen_clitics: &[],
+    enclitics: &[],
+    enclitic_hosts: &[],
+    accented_enclitic_hosts: &[],
+    enclitic_exceptions: &[],
+    is_verb: is_never_verb,
+    compound_exceptions: &[],
+    capitalized_nouns: true,
+};
+
+fn is_word_like(word: &str) -> bool {
+    word.chars().any(char::is_alphanumeric)
+}
+
+fn normalize_apostrophes(word: &str) -> String {
+    word.to_lowercase().replace('’', "'")
+}
+
+fn remove_accents(word: &str) -> String {
+    word.chars()
+        .map(|c| match c {
+            'á' | 'à' => 'a',
+            'é' | 'è' => 'e',
+            'í' | 'ì' => 'i',
+            'ó' | 'ò' => 'o',
+            'ú' | 'ù' => 'u',
+            _ => c,
+        })
+        .collect::<String>()
+}
+
+fn push_plain<'a>(tokens: &mut Vec<Token<'a>>, text: &'a str) {
+    tokens.push(Token {
+        text,
+        base_form: None,
+    });
+}
+
+impl EuropeanTokenizer {
+    /// Joins words separated only by hyphens back together, returning each
+    /// joined part as (start, end) byte offsets into the text.
+    fn join_hyphenated(&self, bounds: &[(usize, &str)], start: usize) -> Vec<(usize, usize)> {
+        let (offset, word) = bounds[start];
+        let mut parts = vec![(offset, offset + word.len())];
+
+        let mut index = start + 1;
+        while index + 1 < bounds.len()
+            && HYPHENS.contains(&bounds[index].1)
+            && is_word_like(bounds[index + 1].1)
+        {
+            let (offset, word) = bounds[index + 1];
+            parts.push((offset, offset + word.len()));
+            index += 2;
+        }
+
+        parts
+    }
+
+    fn push_word<'a>(&self, tokens: &mut Vec<Token<'a>>, word: &'a str) {
+        let lowercase = normalize_apostrophes(word);
+
+        if let Some(elision) = self
+            .elisions
+            .iter()
+            .find(|elision| lowercase.starts_with(*elision) && lowercase.len() > elision.len())
+        {
+            let split = word
+                .char_indices()
+                .nth(elision.chars().count())
+                .map(|(index, _)| index)
+                .unwrap();
+
+            push_plain(tokens, &word[..split]);
+            return self.push_word(tokens, &word[split..]);
+        }
+
+        if let Some(elision) = self
+            .trailing_elisions
+            .iter()
+            .find(|elision| lowercase.ends_with(*elision) && lowercase.len() > elision.len())
+        {
+            let split = word
+                .char_indices()
+                .rev()
+                .nth(elision.chars().count() - 1)
+                .map(|(index, _)| index)
+                .unwrap();
+
+            push_plain(tokens, &word[..split]);
+            push_plain(tokens, &word[split..]);
+            return;
+        }
+
+        match self.split_enclitics(word) {
+            Some((stem_end, clitic_ends, infinitive)) => {
+                let stem = &word[..stem_end];
+                tokens.push(Token {
+                    text: stem,
+                    base_form: if infinitive != stem.to_lowercase() {
+                        Some(infinitive)
+                    } else {
+                        None
+                    },
+                });
+
+                let mut clitic_start = stem_end;
+                for clitic_end in clitic_ends {
+                    push_plain(tokens, &word[clitic_start..clitic_end]);
+                    clitic_start = clitic_end;
+                }
+            }
+            None => push_plain(tokens, word),
+        }
+    }
+
+    /// Finds the infinitive of a verb form enclitics may attach to, replacing
+    /// the ending of the first host rule that gives a known verb.
+    fn get_infinitive(&self, unaccented: &str, hosts: &[HostRule]) -> Option<String> {
+        hosts
+            .iter()
+            .filter_map(|host| Some((unaccented.strip_suffix(host.ending)?, host)))
+            .flat_map(|(stem, host)| {
+                host.infinitive_endings
+                    .iter()
+                    .map(move |ending| format!("{}{}", stem, ending))
+            })
+            .find(|infinitive| (self.is_verb)(infinitive))
+    }
+
+    /// Finds the pronouns attached to the end of a verb, returning where the
+    /// verb ends, where each pronoun ends and the verb's infinitive.
+    fn split_enclitics(&self, word: &str) -> Option<(usize, Vec<usize>, String)> {
+        if self.enclitics.is_empty() {
+            return None;
+        }
+
+        let lowercase = word.to_lowercase();
+        if lowercase.len() != word.len() || self.enclitic_exceptions.contains(&&lowercase[..]) {
+            return None;
+        }
+
+        let mut stem_end = word.len();
+        let mut clitic_ends: Vec<usize> = vec![];
+        let mut split = None;
+
+        // at most two pronouns can follow a verb, dá-me-lo, and the most that
+        // leave a known verb are split off, cóme-te-lo rather than cómete-lo
+        for _ in 0..2 {
+            let remaining = &lowercase[..stem_end];
+            let clitic = self
+                .enclitics
+                .iter()
+                .filter(|clitic| remaining.ends_with(*clitic))
+                .max_by_key(|clitic| clitic.len());
+
+            let clitic = match clitic {
+                Some(clitic) => clitic,
+                None => break,
+            };
+
+            let stem = &remaining[..remaining.len() - clitic.len()];
+            if stem.chars().count() < 2 {
+                break;
+            }
+
+            clitic_ends.insert(0, stem_end);
+            stem_end -= clitic.len();
+
+            let unaccented = remove_accents(stem);
+            let mut infinitive = self.get_infinitive(&unaccented, self.enclitic_hosts);
+
+            // an accent kept only because of the added syllables, dámelo
+            if infinitive.is_none() && unaccented != stem {
+                infinitive = self.get_infinitive(&unaccented, self.accented_enclitic_hosts);
+            }
+
+            if let Some(infinitive) = infinitive {
+                split = Some((stem_end, clitic_ends.clone(), infinitive));
+            }
+        }
+
+        split
+    }
+}
+
+impl Tokenizer for EuropeanTokenizer {
+    fn get_words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.get_tokens(text)
+            .into_iter()
+            .map(|token| token.text)
+            .collect::<Vec<&str>>()
+    }
+
+    fn get_tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let bounds = text
+            .split_word_bound_indices()
+            .collect::<Vec<(usize, &str)>>();
+        let mut tokens: Vec<Token> = vec![];
+
+        let mut index = 0;
+        while index < bounds.len() {
+            let (offset, word) = bounds[index];
+
+            if !is_word_like(word) {
+                push_plain(&mut tokens, word);
+                index += 1;
+                continue;
+            }
+
+            let parts = self.join_hyphenated(&bounds, index);
+            index += parts.len() * 2 - 1;
+
+            // clitic pronouns can only close a hyphenated word, as in dis-le-moi,
+            // everything before them is a single compound word like arc-en-ciel
+            let compound = &text[offset..parts[parts.len() - 1].1];
+            let mut clitic_count = 0;
+            if !self
+                .compound_exceptions
+                .contains(&&normalize_apostrophes(compound)[..])
+            {
+                while clitic_count + 1 < parts.len() {
+                    let (start, end) = parts[parts.len() - 1 - clitic_count];
+                    if !self
+                        .hyphen_clitics
+                        .contains(&&text[start..end].to_lowercase()[..])
+                    {
+                        break;
+                    }
+                    clitic_count += 1;
+                }
+            }
+
+            let compound_end = parts[parts.len() - 1 - clitic_count].1;
+            self.push_word(&mut tokens, &text[offset..compound_end]);
+
+            let mut previous_end = compound_end;
+            for &(start, end) in parts.iter().skip(parts.len() - clitic_count) {
+                push_plain(&mut tokens, &text[previous_end..start]);
+                push_plain(&mut tokens, &text[start..end]);
+                previous_end = end;
+            }
+        }
+
+        tokens
+    }
+
+    fn get_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        text.split_sentence_bounds().collect::<Vec<&str>>()
+    }
+
+    fn is_punctuation(&self, word: &str) -> bool {
+        // guillemets and inverted marks aren't part of the shared stop list
+        STOP_CHARS.contains(word) || !is_word_like(word)
+    }
+
+    fn is_capitalized(&self, word: &str) -> bool {
+        !self.capitalized_nouns && is_capitalized(word)
+    }
+}
@@ -0,0 +1,64 @@
+use super::morphemes::{self, load_segmenter};
+use super::sentence::{split_sentences, SentenceRules};
+use super::{is_latin_word, Token, Tokenizer};
+use lazy_static::lazy_static;
+use lindera::segmenter::Segmenter;
+use lindera::token::Token as Morpheme;
+
+// IPADIC details are pos,pos1,pos2,pos3,conj_type,conj_form,base,reading,pronunciation
+const BASE_FORM_DETAIL: usize = 6;
+
+lazy_static! {
+    // the IPADIC dictionary is embedded in the binary
+    static ref SEGMENTER: Option<Segmenter> = load_segmenter("embedded://ipadic");
+}
+
+fn get_base_form(morpheme: &mut Morpheme, surface: &str) -> Option<String> {
+    morpheme
+        .get_detail(BASE_FORM_DETAIL)
+        .filter(|&base_form| base_form != surface && base_form != "*")
+        .map(|base_form| base_form.to_string())
+}
+
+/// Segments text with lindera and the full IPADIC dictionary, the one MeCab
+/// uses. Conjugated words carry their dictionary form.
+pub struct JapaneseTokenizer;
+
+static SENTENCE_RULES: SentenceRules = SentenceRules {
+    terminators: &['。', '！', '？', '!', '?'],
+    quote_opens: &['「', '『'],
+    quote_closes: &['」', '』'],
+    // particles that continue a sentence after a closing quote, as in 「…。」と言った
+    continuations: &['と', 'っ'],
+};
+
+impl Tokenizer for JapaneseTokenizer {
+    fn get_words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.get_tokens(text)
+            .into_iter()
+            .map(|token| token.text)
+            .collect::<Vec<&str>>()
+    }
+
+    fn get_tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        match *SEGMENTER {
+            Some(ref segmenter) => morphemes::get_tokens(segmenter, text, get_base_form),
+            None => vec![Token {
+                text,
+                base_form: None,
+            }],
+        }
+    }
+
+    fn get_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        split_sentences(text, &SENTENCE_RULES)
+    }
+
+    fn is_foreign_word(&self, word: &str) -> bool {
+        is_latin_word(word)
+    }
+
+    fn is_available(&self) -> bool {
+        SEGMENTER.is_some()
+    }
+}
@@ -0,0 +1,94 @@
+use super::morphemes::{self, load_segmenter};
+use super::{is_latin_word, Token, Tokenizer};
+use lazy_static::lazy_static;
+use lindera::segmenter::Segmenter;
+use lindera::token::Token as Morpheme;
+use unicode_segmentation::UnicodeSegmentation;
+
+// ko-dic details are pos,semantic_class,final_consonant,reading,type,first_pos,last_pos,expression
+const POS_DETAIL: usize = 0;
+const TYPE_DETAIL: usize = 4;
+const EXPRESSION_DETAIL: usize = 7;
+
+// parts of speech whose dictionary form is their stem followed by 다: verbs,
+// adjectives, auxiliaries, the copulas and the suffixes of 하다 verbs
+const PREDICATE_TAGS: &[&str] = &["VV", "VA", "VX", "VCP", "VCN", "XSV", "XSA"];
+
+lazy_static! {
+    // the ko-dic dictionary is embedded in the binary
+    static ref SEGMENTER: Option<Segmenter> = load_segmenter("embedded://ko-dic");
+}
+
+fn get_base_form(morpheme: &mut Morpheme, surface: &str) -> Option<String> {
+    let tag = morpheme.get_detail(POS_DETAIL)?.split('+').next()?;
+    if !PREDICATE_TAGS.contains(&tag) {
+        return None;
+    }
+
+    // a conjugated form is listed with the morphemes it's made of, as
+    // 가/VV/*+았/EP/* for 갔
+    let is_inflected = morpheme.get_detail(TYPE_DETAIL) == Some("Inflect");
+    let stem = if is_inflected {
+        morpheme.get_detail(EXPRESSION_DETAIL)?.split('/').next()?
+    } else {
+        surface
+    };
+
+    let base_form = format!("{}다", stem);
+    if base_form == surface {
+        return None;
+    }
+
+    Some(base_form)
+}
+
+/// Segments text with lindera and ko-dic, the dictionary of mecab-ko, which
+/// separates the particles and endings Korean attaches to each eojeol so that
+/// 학교에 and 학교는 are both counted under 학교. Verbs and adjectives carry
+/// their dictionary form.
+pub struct KoreanTokenizer;
+
+impl Tokenizer for KoreanTokenizer {
+    fn get_words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.get_tokens(text)
+            .into_iter()
+            .map(|token| token.text)
+            .collect::<Vec<&str>>()
+    }
+
+    fn get_tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        match *SEGMENTER {
+            Some(ref segmenter) => morphemes::get_tokens(segmenter, text, get_base_form),
+            None => vec![Token {
+                text,
+                base_form: None,
+            }],
+        }
+    }
+
+    fn get_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        text.split_sentence_bounds().collect::<Vec<&str>>()
+    }
+
+    fn is_foreign_word(&self, word: &str) -> bool {
+        is_latin_word(word)
+    }
+
+    fn is_available(&self) -> bool {
+        SEGMENTER.is_some()
+    }
+
+    fn get_base_form(&self, word: &str) -> Option<String> {
+        let tokens = self.get_tokens(word);
+
+        match tokens.first() {
+            Some(token) if token.text.len() < word.len() || token.base_form.is_some() => Some(
+                token
+                    .base_form
+                    .clone()
+                    .unwrap_or_else(|| token.text.to_string()),
+            ),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+
+// "form lemma" pairs covering irregular verbs, plurals and comparatives along
+// with the regular inflections of common words. A word listed as its own lemma
+// only looks inflected, and is kept as it is.
+const ENGLISH_LEMMAS_TXT: &str = include_str!("../../data/en/lemmas.txt");
+const ENGLISH_WORDS_TXT: &str = include_str!("../../data/en/words.txt");
+
+/// Finds the lemma of an inflected word, for languages whose tokenizer
+/// doesn't already give dictionary forms.
+pub trait Lemmatizer: Send + Sync {
+    /// Returns the lemma of the word, if it isn't the word itself.
+    fn get_lemma(&self, word: &str) -> Option<String>;
+}
+
+/// An inflectional suffix along with the endings that may replace it, tried in
+/// order. Stems ending in one of the kept letters aren't inflected by it.
+struct SuffixRule {
+    suffix: &'static str,
+    endings: &'static [&'static str],
+    kept_after: &'static [char],
+}
+
+// "-ies" comes before "-es" and "-s" so that "studies" is tried as "study"
+// first, and "-e" is restored before it's left off, as in "hoped" for "hope"
+static ENGLISH_SUFFIX_RULES: &[SuffixRule] = &[
+    SuffixRule {
+        suffix: "ies",
+        endings: &["ie", "y"],
+        kept_after: &[],
+    },
+    SuffixRule {
+        suffix: "es",
+        endings: &["e", ""],
+        kept_after: &[],
+    },
+    SuffixRule {
+        suffix: "s",
+        endings: &[""],
+        kept_after: &['s', 'u'],
+    },
+    SuffixRule {
+        suffix: "ied",
+        endings: &["y", "ie"],
+        kept_after: &[],
+    },
+    SuffixRule {
+        suffix: "ed",
+        endings: &["e", ""],
+        kept_after: &[],
+    },
+    SuffixRule {
+        suffix: "ing",
+        endings: &["e", ""],
+        kept_after: &[],
+    },
+];
+
+// shorter words are rarely inflected, like "was", "bus" or "red"
+const MIN_INFLECTED_LENGTH: usize = 4;
+
+fn is_vowel(c: char) -> bool {
+    "aeiouy".contains(c)
+}
+
+fn has_doubled_consonant(stem: &str) -> bool {
+    let mut chars = stem.chars().rev();
+    match (chars.next(), chars.next()) {
+        (Some(last), Some(previous)) => last == previous && !is_vowel(last),
+        _ => false,
+    }
+}
+
+/// Whether an "-e" dropped before the suffix could have ended the stem, which
+/// never follows a doubled consonant or a "w", "x" or "y", as in "stepped" or
+/// "employed".
+fn may_restore_e(stem: &str) -> bool {
+    !has_doubled_consonant(stem) && !stem.ends_with(|c| "wxy".contains(c))
+}
+
+/// Looks words up in a bundled list of inflected forms, and strips the
+/// inflectional suffixes of the words it doesn't list as long as what's left
+/// is a known word. Unknown words are left as they are.
+pub struct ListLemmatizer {
+    lemmas: HashMap<&'static str, &'static str>,
+    words: HashSet<&'static str>,
+    suffix_rules: &'static [SuffixRule],
+}
+
+impl ListLemmatizer {
+    fn load(
+        lemmas_text: &'static str,
+        words_text: &'static str,
+        suffix_rules: &'static [SuffixRule],
+    ) -> ListLemmatizer {
+        ListLemmatizer {
+            lemmas: lemmas_text
+                .lines()
+                .filter_map(|line| {
+                    let mut parts = line.split(' ');
+                    Some((parts.next()?, parts.next()?))
+                })
+                .collect(),
+            words: words_text
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .collect(),
+            suffix_rules,
+        }
+    }
+
+    fn get_rule_lemma(&self, word: &str) -> Option<String> {
+        if word.len() < MIN_INFLECTED_LENGTH || !word.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+
+        for rule in self.suffix_rules {
+            let stem = match word.strip_suffix(rule.suffix) {
+                Some(stem) if stem.chars().any(is_vowel) && !stem.ends_with(rule.kept_after) => {
+                    stem
+                }
+                _ => continue,
+            };
+
+            for ending in rule.endings {
+                if *ending == "e" && !may_restore_e(stem) {
+                    continue;
+                }
+
+                let lemma = format!("{}{}", stem, ending);
+                if self.words.contains(&lemma[..]) {
+                    return Some(lemma);
+                }
+            }
+
+            // a final consonant is doubled before the suffix, as in "stopped"
+            if has_doubled_consonant(stem) && self.words.contains(&stem[..stem.len() - 1]) {
+                return Some(stem[..stem.len() - 1].to_string());
+            }
+        }
+
+        None
+    }
+}
+
+impl Lemmatizer for ListLemmatizer {
+    fn get_lemma(&self, word: &str) -> Option<String> {
+        let word = word.to_lowercase();
+
+        match self.lemmas.get(&word[..]) {
+            Some(lemma) if *lemma == word => None,
+            Some(lemma) => Some(lemma.to_string()),
+            None => self.get_rule_lemma(&word),
+        }
+    }
+}
+
+lazy_static! {
+    pub static ref ENGLISH: ListLemmatizer =
+        ListLemmatizer::load(ENGLISH_LEMMAS_TXT, ENGLISH_WORDS_TXT, ENGLISH_SUFFIX_RULES);
+}
@@ -0,0 +1,121 @@
+use super::{BlockType, ContentText, Mark, MarkType};
+use pulldown_cmark::{Event, Options, Parser, Tag};
+
+fn get_block_type(tag: &Tag) -> Option<BlockType> {
+    match tag {
+        Tag::Paragraph | Tag::TableRow | Tag::FootnoteDefinition(_) => Some(BlockType::Paragraph),
+        Tag::Heading(_) => Some(BlockType::Heading),
+        Tag::BlockQuote => Some(BlockType::Quote),
+        Tag::Item => Some(BlockType::ListItem),
+        _ => None,
+    }
+}
+
+fn get_mark(tag: Tag) -> Option<(MarkType, Option<String>)> {
+    match tag {
+        Tag::Emphasis => Some((MarkType::Emphasis, None)),
+        Tag::Strong => Some((MarkType::Strong, None)),
+        Tag::Strikethrough => Some((MarkType::Strikethrough, None)),
+        Tag::Link(_, url, _) => Some((MarkType::Link, Some(url.to_string()))),
+        _ => None,
+    }
+}
+
+// blocks are put on lines of their own, so they become paragraphs of the text
+fn end_line(text: &mut String) {
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+fn push_text(
+    text: &mut String,
+    blocks: &mut Vec<(usize, BlockType)>,
+    block_stack: &[BlockType],
+    node: &str,
+) {
+    if text.is_empty() || text.ends_with('\n') {
+        // a list item or quote holds paragraphs of its own type
+        let block_type = block_stack
+            .iter()
+            .rev()
+            .find(|block_type| **block_type != BlockType::Paragraph)
+            .copied()
+            .unwrap_or(BlockType::Paragraph);
+        blocks.push((text.len(), block_type));
+    }
+
+    text.push_str(node);
+}
+
+/// Takes the text nodes out of markdown, with every block on a line of its
+/// own. Code blocks and images aren't text to be read, so they're left out.
+pub fn parse_markdown(content: &str) -> ContentText {
+    let mut text = String::with_capacity(content.len());
+    let mut blocks: Vec<(usize, BlockType)> = vec![];
+    let mut marks: Vec<Mark> = vec![];
+
+    // the blocks and marks the parser is inside of, innermost last
+    let mut block_stack: Vec<BlockType> = vec![];
+    let mut mark_stack: Vec<(MarkType, usize, Option<String>)> = vec![];
+    let mut skip_depth = 0;
+
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES;
+    for event in Parser::new_ext(content, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Start(Tag::Image(..)) => skip_depth += 1,
+            Event::End(Tag::CodeBlock(_)) | Event::End(Tag::Image(..)) => {
+                skip_depth -= 1;
+                end_line(&mut text);
+            }
+            _ if skip_depth > 0 => {}
+            Event::Start(tag) => {
+                if let Some(block_type) = get_block_type(&tag) {
+                    end_line(&mut text);
+                    block_stack.push(block_type);
+                } else if let Some((mark_type, url)) = get_mark(tag) {
+                    mark_stack.push((mark_type, text.len(), url));
+                }
+            }
+            // table cells are kept apart by a space
+            Event::End(Tag::TableCell) if !text.ends_with(char::is_whitespace) => text.push(' '),
+            Event::End(tag) => {
+                if get_block_type(&tag).is_some() {
+                    end_line(&mut text);
+                    block_stack.pop();
+                } else if get_mark(tag).is_some() {
+                    if let Some((mark_type, start, url)) = mark_stack.pop() {
+                        if start < text.len() {
+                            marks.push(Mark {
+                                mark_type,
+                                start,
+                                end: text.len(),
+                                url,
+                            });
+                        }
+                    }
+                }
+            }
+            Event::Text(node) => push_text(&mut text, &mut blocks, &block_stack, &node),
+            Event::Code(node) => {
+                let start = text.len();
+                push_text(&mut text, &mut blocks, &block_stack, &node);
+                marks.push(Mark {
+                    mark_type: MarkType::Code,
+                    start,
+                    end: text.len(),
+                    url: None,
+                });
+            }
+            Event::SoftBreak => text.push(' '),
+            Event::HardBreak => end_line(&mut text),
+            _ => {}
+        }
+    }
+
+    ContentText {
+        text,
+        blocks: Some(blocks),
+        marks,
+    }
+}
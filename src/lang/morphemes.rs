@@ -0,0 +1,73 @@
+use super::Token;
+use lindera::dictionary::load_dictionary;
+use lindera::mode::Mode;
+use lindera::segmenter::Segmenter;
+use lindera::token::Token as Morpheme;
+use std::borrow::Cow;
+
+/// Loads a lindera dictionary embedded in the binary, leaving its language
+/// unsupported in a build where it couldn't be.
+pub fn load_segmenter(uri: &str) -> Option<Segmenter> {
+    match load_dictionary(uri) {
+        Ok(dictionary) => {
+            Some(Segmenter::new(Mode::Normal, dictionary, None).keep_whitespace(true))
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            None
+        }
+    }
+}
+
+/// Segments text with a lindera dictionary, giving each token the base form
+/// found in its dictionary details. The tokens always cover the whole text.
+pub fn get_tokens<'a>(
+    segmenter: &Segmenter,
+    text: &'a str,
+    get_base_form: fn(&mut Morpheme, &str) -> Option<String>,
+) -> Vec<Token<'a>> {
+    if text.is_empty() {
+        return vec![];
+    }
+
+    let segments = match segmenter.segment(Cow::Borrowed(text)) {
+        Ok(segments) => segments,
+        Err(_) => {
+            return vec![Token {
+                text,
+                base_form: None,
+            }]
+        }
+    };
+
+    let mut tokens: Vec<Token> = vec![];
+    let mut end = 0;
+
+    for mut segment in segments {
+        // anything lindera leaves out is kept so the tokens still cover the text
+        if segment.byte_start > end {
+            tokens.push(Token {
+                text: &text[end..segment.byte_start],
+                base_form: None,
+            });
+        }
+
+        let surface = &text[segment.byte_start..segment.byte_end];
+        let base_form = get_base_form(&mut segment, surface);
+
+        tokens.push(Token {
+            text: surface,
+            base_form,
+        });
+        end = segment.byte_end;
+    }
+
+    if end < text.len() {
+        tokens.push(Token {
+            text: &text[end..],
+            base_form: None,
+        });
+    }
+
+    tokens
+}
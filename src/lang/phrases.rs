@@ -0,0 +1,184 @@
+use super::{
+    chinese, get_lemmatizer, get_tokenizer, get_words_and_base_forms, is_chinese, PhraseSpan,
+    TokenType,
+};
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+
+// phrasal verbs and fixed expressions, one per line
+const ENGLISH_PHRASES_TXT: &str = include_str!("../../data/en/phrases.txt");
+// chengyu in simplified characters, one per line
+const CHINESE_PHRASES_TXT: &str = include_str!("../../data/zh/chengyu.txt");
+
+/// The phrases of a language, under the keys articles are matched by.
+struct PhraseList {
+    phrases: HashSet<String>,
+    // the most words, or characters for unspaced languages, of any phrase
+    max_parts: usize,
+}
+
+impl PhraseList {
+    fn new(phrases: HashSet<String>, lang: &str) -> PhraseList {
+        let max_parts = phrases
+            .iter()
+            .map(|phrase| get_part_count(phrase, lang))
+            .max()
+            .unwrap_or(0);
+
+        PhraseList { phrases, max_parts }
+    }
+
+    fn load(text: &'static str, lang: &str) -> PhraseList {
+        let phrases = text
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .filter_map(|line| get_phrase_key(line, lang))
+            .collect::<HashSet<String>>();
+
+        PhraseList::new(phrases, lang)
+    }
+}
+
+lazy_static! {
+    static ref ENGLISH: PhraseList = PhraseList::load(ENGLISH_PHRASES_TXT, "en");
+    static ref CHINESE: PhraseList = PhraseList::load(CHINESE_PHRASES_TXT, "zh");
+}
+
+fn get_bundled_phrases(lang: &str) -> Option<&'static PhraseList> {
+    if lang == "en" {
+        Some(&*ENGLISH)
+    } else if is_chinese(lang) {
+        Some(&*CHINESE)
+    } else {
+        None
+    }
+}
+
+/// Whether the words of the language are written apart, in which case phrases
+/// are matched word by word by lemma. Otherwise they're matched by their
+/// characters, in simplified characters for Chinese.
+fn is_spaced(lang: &str) -> bool {
+    !is_chinese(lang) && lang != "ja"
+}
+
+fn get_part_count(phrase: &str, lang: &str) -> usize {
+    if is_spaced(lang) {
+        phrase.split(' ').count()
+    } else {
+        phrase.chars().count()
+    }
+}
+
+fn get_part_key(word: &str, base_form: &Option<String>, lang: &str) -> String {
+    if is_chinese(lang) {
+        chinese::to_simplified(word)
+    } else if is_spaced(lang) {
+        base_form.as_deref().unwrap_or(word).to_lowercase()
+    } else {
+        word.to_string()
+    }
+}
+
+/// Returns the key a phrase is stored and matched under, or None if it has no
+/// words in the language.
+pub fn get_phrase_key(phrase: &str, lang: &str) -> Option<String> {
+    let tokenizer = get_tokenizer(lang).ok()?;
+    let (words, base_forms) = get_words_and_base_forms(phrase, tokenizer, get_lemmatizer(lang));
+
+    let parts = words
+        .iter()
+        .zip(&base_forms)
+        .filter(|(word, _)| {
+            word.chars().any(char::is_alphanumeric) && !tokenizer.is_punctuation(word)
+        })
+        .map(|(word, base_form)| get_part_key(word, base_form, lang))
+        .collect::<Vec<String>>();
+
+    if parts.is_empty() {
+        None
+    } else if is_spaced(lang) {
+        Some(parts.join(" "))
+    } else {
+        Some(parts.concat())
+    }
+}
+
+/// Finds the phrases of the bundled list of the language and of the user's
+/// own list in the words. The longest phrase starting at a word wins, and
+/// phrases don't overlap. A phrase only runs over words in a row, so a
+/// phrasal verb split by its object, as in "give it up", isn't found.
+pub fn get_phrase_spans(
+    words: &[String],
+    base_forms: &[Option<String>],
+    token_types: &[String],
+    lang: &str,
+    user_phrases: &[String],
+) -> Vec<PhraseSpan> {
+    let bundled = get_bundled_phrases(lang);
+    let user_phrases = PhraseList::new(user_phrases.iter().cloned().collect(), lang);
+    let max_parts = bundled
+        .map_or(0, |phrases| phrases.max_parts)
+        .max(user_phrases.max_parts);
+    let is_phrase = |key: &str| {
+        user_phrases.phrases.contains(key)
+            || matches!(bundled, Some(phrases) if phrases.phrases.contains(key))
+    };
+
+    let spaced = is_spaced(lang);
+    let is_word = |index: usize| token_types[index] == TokenType::Word.as_str();
+    let mut spans: Vec<PhraseSpan> = vec![];
+    let mut start = 0;
+
+    while start < words.len() {
+        if !is_word(start) {
+            start += 1;
+            continue;
+        }
+
+        let mut key = String::new();
+        let mut text = String::new();
+        let mut part_count = 0;
+        let mut longest: Option<(usize, String)> = None;
+
+        for index in start..words.len() {
+            if token_types[index] == TokenType::Whitespace.as_str()
+                && spaced
+                && !words[index].contains('\n')
+            {
+                continue;
+            }
+            if !is_word(index) {
+                break;
+            }
+
+            let part = get_part_key(&words[index], &base_forms[index], lang);
+            part_count += if spaced { 1 } else { part.chars().count() };
+            if part_count > max_parts {
+                break;
+            }
+
+            if spaced && !key.is_empty() {
+                key.push(' ');
+            }
+            key.push_str(&part);
+            text.push_str(&words[index]);
+
+            if index > start && is_phrase(&key) {
+                // the phrase is stored under its lemmas, or as written when
+                // its characters are its key
+                let phrase = if spaced { key.clone() } else { text.clone() };
+                longest = Some((index + 1, phrase));
+            }
+        }
+
+        match longest {
+            Some((end, phrase)) => {
+                spans.push(PhraseSpan { phrase, start, end });
+                start = end;
+            }
+            None => start += 1,
+        }
+    }
+
+    spans
+}
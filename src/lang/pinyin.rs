@@ -0,0 +1,208 @@
+use super::chinese;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+// CC-CEDICT lines, "traditional simplified [pin1 yin1] /definitions/", with
+// the definitions left out of the bundled copy. scripts/update_cedict.sh
+// replaces it with the latest release.
+const CEDICT_TXT: &str = include_str!("../../data/zh/cedict.txt");
+// "character pin1" lines giving every character a reading, for the words and
+// characters the dictionary doesn't have
+const CHARACTERS_TXT: &str = include_str!("../../data/zh/characters.txt");
+
+struct PinyinDictionary {
+    readings: HashMap<&'static str, &'static str>,
+    max_chars: usize,
+}
+
+impl PinyinDictionary {
+    fn load() -> PinyinDictionary {
+        let mut readings: HashMap<&'static str, &'static str> = HashMap::new();
+        let mut max_chars = 0;
+
+        for line in CEDICT_TXT.lines().filter(|line| !line.starts_with('#')) {
+            let mut parts = line.splitn(3, ' ');
+            let (traditional, simplified, rest) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(traditional), Some(simplified), Some(rest)) => {
+                    (traditional, simplified, rest)
+                }
+                _ => continue,
+            };
+
+            let reading = match rest.find(']') {
+                Some(end) if rest.starts_with('[') => &rest[1..end],
+                _ => continue,
+            };
+
+            max_chars = max_chars.max(simplified.chars().count());
+
+            // capitalized names are listed next to the common words written the
+            // same way, and only read as names when they're the only entry
+            for form in &[simplified, traditional] {
+                readings
+                    .entry(form)
+                    .and_modify(|current| {
+                        if is_name_reading(current) && !is_name_reading(reading) {
+                            *current = reading;
+                        }
+                    })
+                    .or_insert(reading);
+            }
+        }
+
+        // a character's entries aren't listed by how common its readings are,
+        // so a lone character is always read the way this table gives it
+        for line in CHARACTERS_TXT.lines().filter(|line| !line.starts_with('#')) {
+            if let Some((character, reading)) = line.split_once(' ') {
+                readings.insert(character, reading);
+            }
+        }
+
+        PinyinDictionary {
+            readings,
+            max_chars,
+        }
+    }
+}
+
+fn is_name_reading(reading: &str) -> bool {
+    reading.starts_with(char::is_uppercase)
+}
+
+lazy_static! {
+    static ref DICTIONARY: PinyinDictionary = PinyinDictionary::load();
+}
+
+fn is_han(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c)
+}
+
+const TONE_MARKS: &[(char, [char; 4])] = &[
+    ('a', ['ā', 'á', 'ǎ', 'à']),
+    ('e', ['ē', 'é', 'ě', 'è']),
+    ('i', ['ī', 'í', 'ǐ', 'ì']),
+    ('o', ['ō', 'ó', 'ǒ', 'ò']),
+    ('u', ['ū', 'ú', 'ǔ', 'ù']),
+    ('ü', ['ǖ', 'ǘ', 'ǚ', 'ǜ']),
+];
+
+/// Turns a numbered syllable such as hao3 into its tone marked form, hǎo.
+fn to_tone_mark(syllable: &str) -> String {
+    let syllable = syllable.replace("u:", "ü").replace('v', "ü");
+    let tone = match syllable.chars().last().and_then(|c| c.to_digit(10)) {
+        Some(tone) => tone as usize,
+        None => return syllable,
+    };
+    let letters = &syllable[..syllable.len() - 1];
+
+    if !(1..=4).contains(&tone) {
+        return letters.to_string();
+    }
+
+    // a and e always take the mark, o takes it in ou, otherwise the last vowel
+    let lowercase = letters.to_lowercase();
+    let mark_index = lowercase
+        .find('a')
+        .or_else(|| lowercase.find('e'))
+        .or_else(|| lowercase.find("ou"))
+        .or_else(|| {
+            lowercase
+                .char_indices()
+                .rev()
+                .find(|(_, c)| TONE_MARKS.iter().any(|(vowel, _)| vowel == c))
+                .map(|(index, _)| index)
+        });
+
+    let mark_index = match mark_index {
+        Some(mark_index) => mark_index,
+        None => return letters.to_string(),
+    };
+
+    letters
+        .char_indices()
+        .map(|(index, c)| {
+            if index != mark_index {
+                return c.to_string();
+            }
+
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let marked = TONE_MARKS
+                .iter()
+                .find(|(vowel, _)| *vowel == lower)
+                .map(|(_, marks)| marks[tone - 1])
+                .unwrap_or(c);
+
+            if c.is_uppercase() {
+                marked.to_uppercase().to_string()
+            } else {
+                marked.to_string()
+            }
+        })
+        .collect::<String>()
+}
+
+/// Joins the syllables of a word, adding an apostrophe before a syllable
+/// starting with a vowel, as in xī'ān.
+fn join_syllables(syllables: &[String]) -> String {
+    let mut word = String::new();
+
+    for (index, syllable) in syllables.iter().enumerate() {
+        let starts_with_vowel = syllable
+            .chars()
+            .next()
+            .map(|c| "aeoāáǎàēéěèōóǒò".contains(c.to_lowercase().next().unwrap_or(c)))
+            .unwrap_or(false);
+
+        if index > 0 && starts_with_vowel {
+            word.push('\'');
+        }
+
+        word.push_str(syllable);
+    }
+
+    word
+}
+
+/// Reads a word as given by jieba, falling back to the longest dictionary
+/// entries it contains when the whole word isn't in the dictionary. A
+/// character without any reading is kept as it is in the partial reading.
+pub fn get_pinyin(word: &str) -> Option<String> {
+    if !word.chars().any(is_han) {
+        return None;
+    }
+
+    let simplified = chinese::to_simplified(word);
+    let chars = simplified.char_indices().collect::<Vec<(usize, char)>>();
+    let mut syllables: Vec<String> = vec![];
+
+    let mut start = 0;
+    while start < chars.len() {
+        let longest = (1..=DICTIONARY.max_chars.min(chars.len() - start))
+            .rev()
+            .find_map(|length| {
+                let begin = chars[start].0;
+                let end = chars
+                    .get(start + length)
+                    .map(|(index, _)| *index)
+                    .unwrap_or(simplified.len());
+
+                DICTIONARY
+                    .readings
+                    .get(&simplified[begin..end])
+                    .map(|reading| (length, reading))
+            });
+
+        match longest {
+            Some((length, reading)) => {
+                syllables.extend(reading.split(' ').map(to_tone_mark));
+                start += length;
+            }
+            None => {
+                syllables.push(chars[start].1.to_string());
+                start += 1;
+            }
+        }
+    }
+
+    Some(join_syllables(&syllables))
+}
@@ -0,0 +1,61 @@
+/// Punctuation based sentence rules for languages written without spaces,
+/// where unicode sentence bounds split poorly around full width marks.
+pub struct SentenceRules {
+    // marks that end a sentence, unless they're inside a quote
+    pub terminators: &'static [char],
+    // quotes and brackets, which keep a sentence together until they're closed
+    pub quote_opens: &'static [char],
+    pub quote_closes: &'static [char],
+    // characters that continue a sentence after a closing quote
+    pub continuations: &'static [char],
+}
+
+/// Splits the text into sentences. A sentence ends after a terminator outside
+/// of quotes, or after a quote closed right after a terminator, and takes the
+/// whitespace that follows it. Newlines always end a sentence, so a quote left
+/// open can't swallow the rest of the text.
+pub fn split_sentences<'a>(text: &'a str, rules: &SentenceRules) -> Vec<&'a str> {
+    let mut sentences: Vec<&str> = vec![];
+    let mut sentence_start = 0;
+    let mut quote_depth = 0usize;
+    let mut pending_end = false;
+    let mut prev: Option<char> = None;
+
+    for (index, c) in text.char_indices() {
+        // a sentence ends before the first character that can't trail it
+        if pending_end && !rules.terminators.contains(&c) && !rules.quote_closes.contains(&c) {
+            let continues_quote = rules.continuations.contains(&c)
+                && matches!(prev, Some(prev) if rules.quote_closes.contains(&prev));
+
+            if continues_quote {
+                pending_end = false;
+            } else if !c.is_whitespace() {
+                sentences.push(&text[sentence_start..index]);
+                sentence_start = index;
+                pending_end = false;
+            }
+        }
+
+        if c == '\n' {
+            quote_depth = 0;
+            pending_end = true;
+        } else if rules.quote_opens.contains(&c) {
+            quote_depth += 1;
+        } else if rules.quote_closes.contains(&c) {
+            quote_depth = quote_depth.saturating_sub(1);
+            if quote_depth == 0 && matches!(prev, Some(prev) if rules.terminators.contains(&prev)) {
+                pending_end = true;
+            }
+        } else if quote_depth == 0 && rules.terminators.contains(&c) {
+            pending_end = true;
+        }
+
+        prev = Some(c);
+    }
+
+    if sentence_start < text.len() {
+        sentences.push(&text[sentence_start..]);
+    }
+
+    sentences
+}
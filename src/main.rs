@@ -5,6 +5,7 @@ mod handlers;
 mod lang;
 mod models;
 mod response;
+mod review;
 mod util;
 
 extern crate argon2;
@@ -41,6 +42,9 @@ async fn main() -> std::io::Result<()> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
     let json_config = web::JsonConfig::default().limit(CONFIG.server.json_max_size);
 
+    // the server doesn't wait for the articles to be segmented
+    actix_web::rt::spawn(article::segment_migrated_articles(pool.clone()));
+
     HttpServer::new(move || {
         let cors = Cors::default()
             .send_wildcard()
@@ -68,16 +72,29 @@ async fn main() -> std::io::Result<()> {
             .service(user::data::get_user_word_data)
             .service(user::data::update_word_status)
             .service(user::data::batch_update_word_status)
+            .service(user::data::get_timeline)
             .service(user::data::update_word_definition)
+            .service(user::dictionary::get_dictionary)
+            .service(user::dictionary::add_dictionary_word)
+            .service(user::dictionary::remove_dictionary_word)
+            .service(user::phrase::get_phrases)
+            .service(user::phrase::add_phrase)
+            .service(user::phrase::remove_phrase)
+            .service(user::review::get_review_queue)
+            .service(user::review::review_word)
             .service(article::edit_article)
             .service(article::create_article)
+            .service(article::get_recommended_articles)
             .service(article::system::get_articles)
             .service(article::system::get_full_article)
+            .service(article::system::get_article_page)
             .service(article::user::get_single_user_article_list)
             .service(article::user::get_all_user_article_list)
             .service(article::user::get_saved_article_list)
             .service(article::user::get_full_article)
+            .service(article::user::get_article_page)
             .service(article::user::delete_article)
+            .service(article::user::resegment_article)
             .service(article::user::save_data::save_article)
             .service(article::user::save_data::remove_saved_article)
             .service(user::data::create_read_data)
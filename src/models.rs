@@ -51,6 +51,9 @@ pub mod user {
             pub study_lang: String,
             pub display_lang: String,
             pub refresh_token: String,
+            pub merge_chinese_scripts: bool,
+            pub page_size: i32,
+            pub page_unit: String,
         }
 
         pub struct UpdateUserOpt {
@@ -60,6 +63,9 @@ pub mod user {
             pub study_lang: Option<String>,
             pub display_lang: Option<String>,
             pub refresh_token: Option<String>,
+            pub merge_chinese_scripts: Option<bool>,
+            pub page_size: Option<i32>,
+            pub page_unit: Option<String>,
         }
 
         impl UpdateUserOpt {
@@ -71,6 +77,9 @@ pub mod user {
                     study_lang: None,
                     display_lang: None,
                     refresh_token: None,
+                    merge_chinese_scripts: None,
+                    page_size: None,
+                    page_unit: None,
                 }
             }
 
@@ -82,6 +91,9 @@ pub mod user {
                     study_lang: req.study_lang,
                     display_lang: req.display_lang,
                     refresh_token: None,
+                    merge_chinese_scripts: req.merge_chinese_scripts,
+                    page_size: req.page_size,
+                    page_unit: req.page_unit,
                 }
             }
         }
@@ -93,6 +105,9 @@ pub mod user {
             pub display_name: String,
             pub study_lang: String,
             pub display_lang: String,
+            pub merge_chinese_scripts: bool,
+            pub page_size: i32,
+            pub page_unit: String,
         }
 
         impl SimpleUser {
@@ -103,6 +118,9 @@ pub mod user {
                     display_name: user.display_name,
                     study_lang: user.study_lang,
                     display_lang: user.display_lang,
+                    merge_chinese_scripts: user.merge_chinese_scripts,
+                    page_size: user.page_size,
+                    page_unit: user.page_unit,
                 }
             }
         }
@@ -200,6 +218,9 @@ pub mod user {
             pub password: Option<String>,
             pub study_lang: Option<String>,
             pub display_lang: Option<String>,
+            pub merge_chinese_scripts: Option<bool>,
+            pub page_size: Option<i32>,
+            pub page_unit: Option<String>,
         }
     }
 
@@ -259,11 +280,19 @@ pub mod user {
         pub mod db {
             use super::*;
 
-            #[derive(Serialize, Deserialize, PostgresMapper)]
-            #[pg_mapper(table = "user_word_data")]
+            // a user's vocabulary by language, built from their rows of user_word,
+            // with the sentences words were marked in from user_word_context
+            #[derive(Serialize, Deserialize)]
             pub struct UserWordData {
                 pub word_status_data: serde_json::Value,
                 pub word_definition_data: serde_json::Value,
+                pub word_context_data: serde_json::Value,
+            }
+
+            // the languages and article the words of a page of word data are from
+            pub struct WordDataFilter {
+                pub langs: Option<Vec<String>>,
+                pub article_id: Option<i32>,
             }
 
             #[derive(Serialize, Deserialize)]
@@ -279,6 +308,48 @@ pub mod user {
                 pub color: String
             }
 
+            #[derive(Serialize, Deserialize, PostgresMapper)]
+            #[pg_mapper(table = "user_dictionary_word")]
+            pub struct DictionaryWord {
+                pub word: String,
+                pub frequency: Option<i32>,
+                pub tag: Option<String>,
+            }
+
+            #[derive(Serialize, Deserialize, PostgresMapper)]
+            #[pg_mapper(table = "user_phrase")]
+            pub struct UserPhrase {
+                pub lang: String,
+                pub phrase: String,
+            }
+
+            #[derive(Serialize, Deserialize, PostgresMapper)]
+            #[pg_mapper(table = "user_word")]
+            pub struct ReviewWord {
+                pub lang: String,
+                pub word: String,
+                pub status: String,
+                pub level: Option<i32>,
+                pub definition: Option<String>,
+                pub ease: f32,
+                pub review_interval: i32,
+                pub repetitions: i32,
+                pub due_on: Option<SystemTime>,
+            }
+
+            // the number of words moved into each status during a period,
+            // where reset counts the words set back to new
+            #[derive(Serialize, Deserialize, PostgresMapper)]
+            #[pg_mapper(table = "user_word_status_change")]
+            pub struct TimelinePeriod {
+                pub lang: String,
+                pub period_start: SystemTime,
+                pub learning: i64,
+                pub known: i64,
+                pub ignored: i64,
+                pub reset: i64,
+            }
+
             #[derive(Serialize, Deserialize, PostgresMapper)]
             #[pg_mapper(table = "read_article_data")]
             pub struct ReadData {
@@ -293,22 +364,38 @@ pub mod user {
             use super::db::*;
             use super::*;
 
+            #[derive(Deserialize)]
+            pub struct GetWordDataRequest {
+                pub lang: Option<String>,
+                pub article_id: Option<i32>,
+                pub offset: Option<i64>,
+                pub limit: Option<i64>,
+            }
+
             #[derive(Serialize)]
             pub struct GetWordDataResponse {
                 pub data: UserWordData,
+                pub has_more: bool,
             }
 
             impl GetWordDataResponse {
-                pub fn new(data: UserWordData) -> GetWordDataResponse {
-                    GetWordDataResponse { data }
+                pub fn new(data: UserWordData, has_more: bool) -> GetWordDataResponse {
+                    GetWordDataResponse { data, has_more }
                 }
             }
 
+            // learning words have a level from 1 to 4, which is 1 if it's missing
             #[derive(Deserialize)]
             pub struct UpdateWordStatusRequest {
                 pub lang: String,
                 pub word: String,
                 pub status: String,
+                pub level: Option<i32>,
+                pub lemmatize: Option<bool>,
+                // the article the word was marked in, and the index of the word
+                // in it, whose sentence is kept when it's marked as learning
+                pub article_id: Option<i32>,
+                pub word_index: Option<i32>,
             }
 
             #[derive(Deserialize)]
@@ -316,6 +403,11 @@ pub mod user {
                 pub lang: String,
                 pub words: Vec<String>,
                 pub status: String,
+                pub level: Option<i32>,
+                pub lemmatize: Option<bool>,
+                // the article the words were marked in, which is only kept in
+                // their history, as batch updates don't keep sentences
+                pub article_id: Option<i32>,
             }
 
             #[derive(Deserialize)]
@@ -323,6 +415,7 @@ pub mod user {
                 pub lang: String,
                 pub word: String,
                 pub definition: String,
+                pub lemmatize: Option<bool>,
             }
 
             #[derive(Deserialize)]
@@ -337,6 +430,104 @@ pub mod user {
                 pub article_id: i32,
             }
 
+            #[derive(Serialize)]
+            pub struct GetDictionaryResponse {
+                pub words: Vec<DictionaryWord>,
+            }
+
+            impl GetDictionaryResponse {
+                pub fn new(words: Vec<DictionaryWord>) -> GetDictionaryResponse {
+                    GetDictionaryResponse { words }
+                }
+            }
+
+            #[derive(Deserialize)]
+            pub struct AddDictionaryWordRequest {
+                pub word: String,
+                pub frequency: Option<i32>,
+                pub tag: Option<String>,
+            }
+
+            #[derive(Deserialize)]
+            pub struct RemoveDictionaryWordRequest {
+                pub word: String,
+            }
+
+            #[derive(Serialize)]
+            pub struct GetPhrasesResponse {
+                pub phrases: Vec<UserPhrase>,
+            }
+
+            impl GetPhrasesResponse {
+                pub fn new(phrases: Vec<UserPhrase>) -> GetPhrasesResponse {
+                    GetPhrasesResponse { phrases }
+                }
+            }
+
+            // the phrase is stored under the lemmas of its words
+            #[derive(Deserialize)]
+            pub struct PhraseRequest {
+                pub lang: String,
+                pub phrase: String,
+            }
+
+            // period is day, week or month, and periods is how many of them
+            // the timeline goes back
+            #[derive(Deserialize)]
+            pub struct GetTimelineRequest {
+                pub lang: Option<String>,
+                pub period: Option<String>,
+                pub periods: Option<i64>,
+            }
+
+            #[derive(Serialize)]
+            pub struct GetTimelineResponse {
+                pub periods: Vec<TimelinePeriod>,
+            }
+
+            impl GetTimelineResponse {
+                pub fn new(periods: Vec<TimelinePeriod>) -> GetTimelineResponse {
+                    GetTimelineResponse { periods }
+                }
+            }
+
+            #[derive(Deserialize)]
+            pub struct GetReviewQueueRequest {
+                pub lang: String,
+                pub limit: Option<i64>,
+            }
+
+            #[derive(Serialize)]
+            pub struct GetReviewQueueResponse {
+                pub words: Vec<ReviewWord>,
+            }
+
+            impl GetReviewQueueResponse {
+                pub fn new(words: Vec<ReviewWord>) -> GetReviewQueueResponse {
+                    GetReviewQueueResponse { words }
+                }
+            }
+
+            // the word is given as the review queue lists it
+            #[derive(Deserialize)]
+            pub struct ReviewWordRequest {
+                pub lang: String,
+                pub word: String,
+                pub grade: i32,
+                pub lemmatize: Option<bool>,
+            }
+
+            #[derive(Serialize)]
+            pub struct ReviewWordResponse {
+                pub word: ReviewWord,
+            }
+
+            impl ReviewWordResponse {
+                pub fn new(word: ReviewWord) -> ReviewWordResponse {
+                    ReviewWordResponse { word }
+                }
+            }
+
             #[derive(Serialize)]
             pub struct GetReadDataResponse {
                 pub data: ReadData,
@@ -368,7 +559,8 @@ pub mod article {
             pub words: Vec<String>,
             pub sentences: serde_json::Value,
             pub unique_words: serde_json::Value,
-            pub page_data: serde_json::Value,
+            pub marks: serde_json::Value,
+            pub phrases: serde_json::Value,
             pub created_on: SystemTime,
             pub is_system: bool,
             pub uploader_id: i32,
@@ -394,6 +586,17 @@ pub mod article {
             pub tags: Vec<String>,
 
             pub unique_word_count: i32,
+            pub difficulty: Option<f32>,
+        }
+
+        #[derive(Serialize)]
+        pub struct ArticleListItem {
+            #[serde(flatten)]
+            pub article: SimpleArticle,
+
+            // percentage of the vocabulary the user knows or is learning
+            pub comprehension: f32,
+            pub new_word_count: i32,
         }
 
         #[derive(Serialize, Deserialize, PostgresMapper)]
@@ -402,6 +605,10 @@ pub mod article {
             pub id: i32,
             pub title: String,
             pub created_on: SystemTime,
+
+            pub lang: String,
+            pub detected_lang: Option<String>,
+            pub lang_confidence: Option<f32>,
         }
 
         #[derive(Serialize, Deserialize, PostgresMapper)]
@@ -419,6 +626,7 @@ pub mod article {
 
             pub lang: String,
             pub tags: Vec<String>,
+            pub content_format: String,
 
             pub word_count: i32,
 
@@ -427,6 +635,81 @@ pub mod article {
             pub word_index_map: serde_json::Value,
             pub stop_word_map: serde_json::Value,
 
+            pub base_forms: Vec<Option<String>>,
+            pub pinyin: Option<Vec<Option<String>>>,
+
+            pub char_offsets: Vec<i32>,
+            pub byte_offsets: Vec<i32>,
+            pub token_types: Vec<String>,
+            pub paragraph_indices: Vec<i32>,
+            pub block_types: Vec<String>,
+
+            pub phrases: serde_json::Value,
+        }
+
+        // what an article's pages are made from
+        #[derive(PostgresMapper)]
+        #[pg_mapper(table = "article")]
+        pub struct ArticlePageSource {
+            pub words: Vec<String>,
+            pub token_types: Vec<String>,
+            pub sentence_stops: Option<Vec<i32>>,
+            pub paragraph_indices: Vec<i32>,
+            pub block_types: Vec<String>,
+            pub marks: serde_json::Value,
+        }
+
+        #[derive(PostgresMapper)]
+        #[pg_mapper(table = "article")]
+        pub struct ArticleSentenceData {
+            pub sentences: Option<serde_json::Value>,
+            pub sentence_stops: Option<Vec<i32>>,
+        }
+
+        // what's looked up for the words on a page
+        #[derive(PostgresMapper)]
+        #[pg_mapper(table = "article")]
+        pub struct ArticleIndexData {
+            pub word_index_map: serde_json::Value,
+            pub stop_word_map: serde_json::Value,
+
+            pub base_forms: Vec<Option<String>>,
+            pub pinyin: Option<Vec<Option<String>>>,
+
+            pub phrases: serde_json::Value,
+        }
+
+        // one page of an article, with the index data of its words only
+        #[derive(Serialize)]
+        pub struct ArticlePage {
+            pub article_id: i32,
+            pub page_index: i32,
+            pub page_count: i32,
+
+            // the index of the first word of the page in the article
+            pub start: i32,
+
+            pub words: Vec<String>,
+            pub base_forms: Vec<Option<String>>,
+            pub pinyin: Option<Vec<Option<String>>>,
+            pub token_types: Vec<String>,
+
+            // word ranges relative to the start of the page
+            pub blocks: Vec<serde_json::Value>,
+            pub marks: serde_json::Value,
+            pub phrases: serde_json::Value,
+
+            // word indices stay those of the article
+            pub word_index_map: serde_json::Value,
+            pub stop_word_map: serde_json::Value,
+        }
+
+        #[derive(Serialize)]
+        pub struct FullArticle {
+            #[serde(flatten)]
+            pub article: ReadArticle,
+
+            // the pages at the page size of the request or of the user
             pub page_data: serde_json::Value,
         }
 
@@ -446,9 +729,24 @@ pub mod article {
         #[derive(Serialize, Deserialize)]
         pub struct ArticleMainData {
             pub content: String,
+            pub content_format: String,
 
             pub word_count: i32,
 
+            pub base_forms: Vec<Option<String>>,
+            pub pinyin: Option<Vec<Option<String>>>,
+
+            pub char_offsets: Vec<i32>,
+            pub byte_offsets: Vec<i32>,
+            pub token_types: Vec<String>,
+            pub paragraph_indices: Vec<i32>,
+            pub block_types: Vec<String>,
+
+            pub difficulty: Option<f32>,
+
+            pub detected_lang: Option<String>,
+            pub lang_confidence: Option<f32>,
+
             pub unique_words: serde_json::Value,
             pub unique_word_count: i32,
 
@@ -458,12 +756,23 @@ pub mod article {
             pub sentences: Option<serde_json::Value>,
             pub sentence_stops: Option<Vec<i32>>,
 
-            pub page_data: serde_json::Value,
+            pub marks: serde_json::Value,
+            pub phrases: serde_json::Value,
         }
 
         #[derive(Serialize, Deserialize)]
         pub struct ArticleContentData {
             pub words: Vec<String>,
+            pub base_forms: Vec<Option<String>>,
+            pub pinyin: Option<Vec<Option<String>>>,
+
+            pub char_offsets: Vec<i32>,
+            pub byte_offsets: Vec<i32>,
+            pub token_types: Vec<String>,
+            pub paragraph_indices: Vec<i32>,
+            pub block_types: Vec<String>,
+
+            pub difficulty: Option<f32>,
 
             pub unique_words: serde_json::Value,
             pub unique_word_count: i32,
@@ -474,7 +783,8 @@ pub mod article {
             pub sentences: Option<serde_json::Value>,
             pub sentence_stops: Option<Vec<i32>>,
 
-            pub page_data: serde_json::Value,
+            pub marks: serde_json::Value,
+            pub phrases: serde_json::Value,
         }
 
         pub struct UpdateArticleMetadataOpt {
@@ -485,6 +795,21 @@ pub mod article {
             pub tags: Option<Vec<String>>,
             pub is_private: Option<bool>,
         }
+
+        pub struct ArticleListFilter {
+            pub lang: Option<String>,
+            pub search: Option<String>,
+            pub min_difficulty: Option<f32>,
+            pub max_difficulty: Option<f32>,
+            pub sort: Option<String>,
+        }
+
+        pub struct RecommendationFilter {
+            pub lang: Option<String>,
+            pub tag: Option<String>,
+            pub min_comprehension: f32,
+            pub max_comprehension: f32,
+        }
     }
 
     pub mod net {
@@ -498,17 +823,20 @@ pub mod article {
             pub offset: Option<i64>,
             pub lang: Option<String>,
             pub search: Option<String>,
+            pub min_difficulty: Option<f32>,
+            pub max_difficulty: Option<f32>,
+            pub sort: Option<String>,
         }
 
         #[derive(Serialize)]
         pub struct GetArticlesResponse {
-            pub articles: Vec<SimpleArticle>,
+            pub articles: Vec<ArticleListItem>,
             pub count: i64,
         }
 
         impl GetArticlesResponse {
             #[inline]
-            pub fn new(articles: Vec<SimpleArticle>) -> GetArticlesResponse {
+            pub fn new(articles: Vec<ArticleListItem>) -> GetArticlesResponse {
                 let count = articles.len() as i64;
                 GetArticlesResponse { articles, count }
             }
@@ -520,30 +848,73 @@ pub mod article {
             pub article_id: i32,
         }
 
+        // the user's page size is used for what's left out
+        #[derive(Deserialize)]
+        pub struct GetFullArticleRequest {
+            pub page_size: Option<i32>,
+            pub page_unit: Option<String>,
+        }
+
         #[derive(Serialize)]
         pub struct GetFullArticleResponse {
-            pub article: ReadArticle,
+            pub article: FullArticle,
         }
 
         impl GetFullArticleResponse {
             #[inline]
-            pub fn new(article: ReadArticle) -> GetFullArticleResponse {
+            pub fn new(article: FullArticle) -> GetFullArticleResponse {
                 GetFullArticleResponse { article }
             }
         }
 
+        // get a page of an article, by its index or a word on it
+        #[derive(Deserialize)]
+        pub struct GetArticlePageRequest {
+            pub page: Option<i32>,
+            pub word_index: Option<i32>,
+            pub page_size: Option<i32>,
+            pub page_unit: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        pub struct GetArticlePageResponse {
+            pub page: ArticlePage,
+        }
+
+        impl GetArticlePageResponse {
+            #[inline]
+            pub fn new(page: ArticlePage) -> GetArticlePageResponse {
+                GetArticlePageResponse { page }
+            }
+        }
+
         // post new article
         #[derive(Deserialize)]
         pub struct NewArticleRequest {
             pub title: String,
             pub author: Option<String>,
             pub content: String,
+            // plain or markdown, plain when left out
+            pub content_format: Option<String>,
             pub content_description: Option<String>,
-            pub language: String,
+            // detected from the content when left out
+            pub language: Option<String>,
             pub tags: Option<Vec<String>>,
             pub is_private: bool,
         }
 
+        #[derive(Serialize)]
+        pub struct LanguageCandidate {
+            pub lang: String,
+            pub confidence: f32,
+        }
+
+        #[derive(Serialize)]
+        pub struct LanguageUndetectedResponse {
+            pub error: &'static str,
+            pub candidates: Vec<LanguageCandidate>,
+        }
+
         #[derive(Serialize)]
         pub struct NewArticleResponse {
             pub article: NewArticle,
@@ -562,6 +933,7 @@ pub mod article {
             pub title: Option<String>,
             pub author: Option<String>,
             pub content: Option<String>,
+            pub content_format: Option<String>,
             pub content_description: Option<String>,
             pub language: Option<String>,
             pub tags: Option<Vec<String>>,
@@ -573,6 +945,16 @@ pub mod article {
             pub article: NewArticle,
         }
 
+        #[derive(Deserialize)]
+        pub struct GetRecommendedArticlesRequest {
+            pub limit: Option<i64>,
+            pub offset: Option<i64>,
+            pub lang: Option<String>,
+            pub tag: Option<String>,
+            pub min_comprehension: Option<f32>,
+            pub max_comprehension: Option<f32>,
+        }
+
         // get user uploaded article list
         #[derive(Deserialize)]
         pub struct GetUserArticlesRequest {
@@ -581,6 +963,9 @@ pub mod article {
             pub user_id: Option<i32>,
             pub lang: Option<String>,
             pub search: Option<String>,
+            pub min_difficulty: Option<f32>,
+            pub max_difficulty: Option<f32>,
+            pub sort: Option<String>,
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::models::gen::net::{ErrorResponse, Message};
+use crate::models::net::{LanguageCandidate, LanguageUndetectedResponse};
 use actix_web::HttpResponse;
 
 #[inline]
@@ -11,6 +12,11 @@ pub fn get_not_found(error: &'static str) -> HttpResponse {
     HttpResponse::NotFound().json(ErrorResponse { error })
 }
 
+#[inline]
+pub fn get_bad_request(error: &'static str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ErrorResponse { error })
+}
+
 pub mod user_res {
     use super::*;
 
@@ -24,6 +30,11 @@ pub mod user_res {
         get_error("user_data_get_fail")
     }
 
+    #[inline]
+    pub fn get_invalid_word_data_page_error() -> HttpResponse {
+        get_bad_request("invalid_word_data_page")
+    }
+
     #[inline]
     pub fn get_user_update_error() -> HttpResponse {
         get_error("user_update_fail")
@@ -34,6 +45,21 @@ pub mod user_res {
         get_error("user_update_word_status_fail")
     }
 
+    #[inline]
+    pub fn get_invalid_word_status_error() -> HttpResponse {
+        get_bad_request("invalid_word_status")
+    }
+
+    #[inline]
+    pub fn get_fetch_timeline_error() -> HttpResponse {
+        get_error("timeline_get_fail")
+    }
+
+    #[inline]
+    pub fn get_invalid_timeline_error() -> HttpResponse {
+        get_bad_request("invalid_timeline")
+    }
+
     #[inline]
     pub fn get_update_word_definition_error() -> HttpResponse {
         get_error("user_update_word_definition_fail")
@@ -82,6 +108,76 @@ pub mod user_res {
     pub fn delete_mark_error() -> HttpResponse {
         get_error("delete_mark_error")
     }
+
+    #[inline]
+    pub fn get_fetch_dictionary_error() -> HttpResponse {
+        get_error("dictionary_get_fail")
+    }
+
+    #[inline]
+    pub fn get_update_dictionary_error() -> HttpResponse {
+        get_error("dictionary_update_fail")
+    }
+
+    #[inline]
+    pub fn get_invalid_dictionary_word_error() -> HttpResponse {
+        get_bad_request("invalid_dictionary_word")
+    }
+
+    #[inline]
+    pub fn get_invalid_page_size_error() -> HttpResponse {
+        get_bad_request("invalid_page_size")
+    }
+
+    #[inline]
+    pub fn get_dictionary_word_missing_error() -> HttpResponse {
+        get_not_found("dictionary_word_missing")
+    }
+
+    #[inline]
+    pub fn get_fetch_phrases_error() -> HttpResponse {
+        get_error("phrase_get_fail")
+    }
+
+    #[inline]
+    pub fn get_update_phrases_error() -> HttpResponse {
+        get_error("phrase_update_fail")
+    }
+
+    #[inline]
+    pub fn get_invalid_phrase_error() -> HttpResponse {
+        get_bad_request("invalid_phrase")
+    }
+
+    #[inline]
+    pub fn get_phrase_missing_error() -> HttpResponse {
+        get_not_found("phrase_missing")
+    }
+
+    #[inline]
+    pub fn get_fetch_review_queue_error() -> HttpResponse {
+        get_error("review_queue_get_fail")
+    }
+
+    #[inline]
+    pub fn get_invalid_review_limit_error() -> HttpResponse {
+        get_bad_request("invalid_review_limit")
+    }
+
+    #[inline]
+    pub fn get_review_word_error() -> HttpResponse {
+        get_error("review_word_fail")
+    }
+
+    #[inline]
+    pub fn get_invalid_grade_error() -> HttpResponse {
+        get_bad_request("invalid_grade")
+    }
+
+    #[inline]
+    pub fn get_review_word_missing_error() -> HttpResponse {
+        get_not_found("review_word_missing")
+    }
 }
 
 pub mod article_res {
@@ -119,6 +215,17 @@ pub mod article_res {
         get_not_found("article_not_found")
     }
 
+    pub fn get_page_not_found() -> HttpResponse {
+        get_not_found("page_not_found")
+    }
+
+    #[inline]
+    pub fn get_article_unsegmented_error() -> HttpResponse {
+        HttpResponse::Conflict().json(ErrorResponse {
+            error: "article_unsegmented",
+        })
+    }
+
     #[inline]
     pub fn get_create_article_error() -> HttpResponse {
         get_error("article_create_fail")
@@ -133,6 +240,39 @@ pub mod article_res {
     pub fn get_edit_article_missing_error() -> HttpResponse {
         get_error("article_missing")
     }
+
+    #[inline]
+    pub fn get_unsupported_language_error() -> HttpResponse {
+        get_bad_request("unsupported_language")
+    }
+
+    #[inline]
+    pub fn get_invalid_sort_error() -> HttpResponse {
+        get_bad_request("invalid_sort")
+    }
+
+    #[inline]
+    pub fn get_invalid_comprehension_error() -> HttpResponse {
+        get_bad_request("invalid_comprehension")
+    }
+
+    #[inline]
+    pub fn get_invalid_page_size_error() -> HttpResponse {
+        get_bad_request("invalid_page_size")
+    }
+
+    #[inline]
+    pub fn get_invalid_content_format_error() -> HttpResponse {
+        get_bad_request("invalid_content_format")
+    }
+
+    #[inline]
+    pub fn get_language_undetected_error(candidates: Vec<LanguageCandidate>) -> HttpResponse {
+        HttpResponse::UnprocessableEntity().json(LanguageUndetectedResponse {
+            error: "language_undetected",
+            candidates,
+        })
+    }
 }
 
 #[inline]
@@ -0,0 +1,112 @@
+// Schedules the reviews of the words a user is learning with SM-2. Each review
+// is graded from 0, for a word that was forgotten, to 5, for perfect recall.
+
+pub const DEFAULT_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+
+pub const MAX_GRADE: i32 = 5;
+const PASSING_GRADE: i32 = 3;
+
+/// Words reviewed this many days apart are remembered well enough to be known.
+pub const MATURE_INTERVAL: i32 = 21;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Schedule {
+    pub ease: f32,
+    /// Days until the next review
+    pub interval: i32,
+    /// Reviews passed in a row
+    pub repetitions: i32,
+}
+
+impl Schedule {
+    pub fn is_mature(&self) -> bool {
+        self.interval >= MATURE_INTERVAL
+    }
+}
+
+impl Default for Schedule {
+    fn default() -> Schedule {
+        Schedule {
+            ease: DEFAULT_EASE,
+            interval: 0,
+            repetitions: 0,
+        }
+    }
+}
+
+pub fn is_valid_grade(grade: i32) -> bool {
+    (0..=MAX_GRADE).contains(&grade)
+}
+
+/// Returns the schedule after a review. A failed review starts the word over
+/// a day later, while a passed one spaces the next review out by the word's
+/// ease, which grows with easy reviews and shrinks with hard ones.
+pub fn get_next_schedule(schedule: &Schedule, grade: i32) -> Schedule {
+    let grade = grade.clamp(0, MAX_GRADE);
+    let difficulty = (MAX_GRADE - grade) as f32;
+    let ease = (schedule.ease + 0.1 - difficulty * (0.08 + difficulty * 0.02)).max(MIN_EASE);
+
+    if grade < PASSING_GRADE {
+        return Schedule {
+            ease,
+            interval: 1,
+            repetitions: 0,
+        };
+    }
+
+    let interval = match schedule.repetitions {
+        0 => 1,
+        1 => 6,
+        _ => (schedule.interval as f32 * schedule.ease).round() as i32,
+    };
+
+    Schedule {
+        ease,
+        interval,
+        repetitions: schedule.repetitions + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_passed_1() {
+        let first = get_next_schedule(&Schedule::default(), 4);
+        assert_eq!(first.interval, 1);
+        assert_eq!(first.repetitions, 1);
+        assert!((first.ease - DEFAULT_EASE).abs() < 1e-6);
+
+        let second = get_next_schedule(&first, 5);
+        assert_eq!(second.interval, 6);
+        assert!(second.ease > first.ease);
+
+        let third = get_next_schedule(&second, 3);
+        assert_eq!(third.interval, 16);
+        assert!(third.ease < second.ease);
+        assert!(!third.is_mature());
+
+        let fourth = get_next_schedule(&third, 4);
+        assert_eq!(fourth.repetitions, 4);
+        assert!(fourth.is_mature());
+    }
+
+    #[test]
+    fn schedule_failed_1() {
+        let schedule = Schedule {
+            ease: 1.4,
+            interval: 15,
+            repetitions: 3,
+        };
+
+        let next = get_next_schedule(&schedule, 1);
+        assert_eq!(next.interval, 1);
+        assert_eq!(next.repetitions, 0);
+        assert!((next.ease - MIN_EASE).abs() < 1e-6);
+
+        assert!(is_valid_grade(0));
+        assert!(!is_valid_grade(MAX_GRADE + 1));
+    }
+}